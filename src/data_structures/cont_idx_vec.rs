@@ -21,6 +21,15 @@ pub struct ContiguousIdxVec<T> {
 }
 
 impl<T: Into<usize> + Copy> ContiguousIdxVec<T> {
+    /// Number of stored items, including deleted ones.
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn num_deleted(&self) -> usize {
+        self.capacity() - self.len
+    }
+
     pub fn is_deleted(&self, id: usize) -> bool {
         self.indices[id].idx() >= self.len
     }
@@ -101,8 +101,9 @@ impl<T> SkipVec<T> {
 
     fn from_entry_vec(mut vec: Vec<Entry<T>>) -> Self {
         for (idx, entry) in vec.iter_mut().enumerate() {
-            entry.prev = idx.checked_sub(1).map_or(EntryIdx::INVALID, EntryIdx::from);
-            entry.next = EntryIdx::from(idx + 1);
+            let idx = EntryIdx::from(idx);
+            entry.prev = idx.checked_sub(1).unwrap_or(EntryIdx::INVALID);
+            entry.next = idx.checked_add(1).unwrap_or(EntryIdx::INVALID);
         }
         if let Some(entry) = vec.last_mut() {
             entry.next = EntryIdx::INVALID;
@@ -139,7 +140,15 @@ impl<T> SkipVec<T> {
             .into_iter()
             .map(|result| result.map(Entry::new))
             .collect::<Result<Vec<_>, _>>()?;
-        vec.sort_unstable_by(|entry1, entry2| entry1.value.cmp(&entry2.value));
+
+        // Most inputs already list their items in sorted order, in which case
+        // a linear check is all that is needed
+        let is_sorted = vec
+            .windows(2)
+            .all(|window| window[0].value <= window[1].value);
+        if !is_sorted {
+            vec.sort_unstable_by(|entry1, entry2| entry1.value.cmp(&entry2.value));
+        }
         Ok(Self::from_entry_vec(vec))
     }
 
@@ -0,0 +1,314 @@
+use crate::{
+    instance::{Instance, NodeIdx},
+    small_indices::SmallIdx,
+};
+use log::{info, trace};
+use std::{cmp::Ordering, ops::ControlFlow, time::Instant};
+
+struct EnumerationState<'a> {
+    original: &'a Instance,
+    max_size: usize,
+    stopped: bool,
+    partial_hs: Vec<NodeIdx>,
+    sorted_hs: Vec<NodeIdx>,
+    hit_count: Vec<usize>,
+    sink: &'a mut dyn FnMut(&[NodeIdx]) -> ControlFlow<()>,
+}
+
+/// Checks whether each node of a hitting set is the only one hitting at least
+/// one edge, i.e., whether no node can be dropped from it.
+fn is_inclusion_minimal(hs: &[NodeIdx], instance: &Instance, hit_count: &mut Vec<usize>) -> bool {
+    hit_count.clear();
+    hit_count.resize(instance.num_edges_total(), 0);
+    for &node in hs {
+        for edge in instance.node(node) {
+            hit_count[edge.idx()] += 1;
+        }
+    }
+
+    hs.iter()
+        .all(|&node| instance.node(node).any(|edge| hit_count[edge.idx()] == 1))
+}
+
+fn enumerate_recursive(instance: &mut Instance, state: &mut EnumerationState<'_>) {
+    if instance.num_edges() == 0 {
+        if is_inclusion_minimal(&state.partial_hs, state.original, &mut state.hit_count) {
+            state.sorted_hs.clone_from(&state.partial_hs);
+            state.sorted_hs.sort_unstable();
+            state.stopped = (state.sink)(&state.sorted_hs).is_break();
+        }
+        return;
+    }
+
+    if state.partial_hs.len() >= state.max_size {
+        return;
+    }
+
+    // Same scheme as edge branching in the solver: the i-th branch includes
+    // the i-th node of the edge and discards all nodes before it.
+    let edge = instance
+        .edges()
+        .iter()
+        .copied()
+        .min_by_key(|&edge| instance.edge_size(edge))
+        .expect("Branching on an empty instance");
+    trace!("Enumerating over nodes of edge {}", edge);
+    let nodes: Vec<_> = instance.edge(edge).collect();
+    let mut num_discarded = 0;
+    for &node in &nodes {
+        instance.delete_node(node);
+        num_discarded += 1;
+
+        instance.delete_incident_edges(node);
+        state.partial_hs.push(node);
+        enumerate_recursive(instance, state);
+        debug_assert_eq!(state.partial_hs.last().copied(), Some(node));
+        state.partial_hs.pop();
+        instance.restore_incident_edges(node);
+
+        if state.stopped
+            || instance
+                .node(node)
+                .any(|edge| instance.edge_size(edge) == 0)
+        {
+            break;
+        }
+    }
+
+    for &node in nodes[..num_discarded].iter().rev() {
+        instance.restore_node(node);
+    }
+}
+
+/// Passes each inclusion-minimal hitting set with at most `max_size` nodes to
+/// `sink` as soon as it is found, without storing them.
+///
+/// Every hitting set is found exactly once and passed with its nodes sorted
+/// by index. The enumeration stops early once `sink` returns
+/// `ControlFlow::Break`, which is reported by the return value.
+pub fn for_each_minimal_hitting_set(
+    instance: &mut Instance,
+    max_size: usize,
+    mut sink: impl FnMut(&[NodeIdx]) -> ControlFlow<()>,
+) -> bool {
+    let original = instance.clone();
+    let mut state = EnumerationState {
+        original: &original,
+        max_size,
+        stopped: false,
+        partial_hs: Vec::new(),
+        sorted_hs: Vec::new(),
+        hit_count: Vec::new(),
+        sink: &mut sink,
+    };
+    enumerate_recursive(instance, &mut state);
+    state.stopped
+}
+
+/// Enumerates all inclusion-minimal hitting sets with at most `max_size` nodes.
+///
+/// Every hitting set is found exactly once and reported with its nodes sorted
+/// by index. Note that the number of such sets can grow exponentially with
+/// `max_size`. If `max_count` is given, the enumeration stops after that many
+/// sets and the returned flag tells whether there are more.
+pub fn enumerate_minimal_hitting_sets(
+    instance: &mut Instance,
+    max_size: usize,
+    max_count: Option<usize>,
+) -> (Vec<Vec<NodeIdx>>, bool) {
+    let time_before = Instant::now();
+    let max_count = max_count.unwrap_or(usize::MAX);
+    let mut minimal_hs = Vec::new();
+    let truncated = for_each_minimal_hitting_set(instance, max_size, |hs| {
+        if minimal_hs.len() >= max_count {
+            return ControlFlow::Break(());
+        }
+        minimal_hs.push(hs.to_vec());
+        ControlFlow::Continue(())
+    });
+
+    info!(
+        "Found {} minimal hitting sets of size at most {} in {:.2?}{}",
+        minimal_hs.len(),
+        max_size,
+        time_before.elapsed(),
+        if truncated {
+            ", stopped before finding all"
+        } else {
+            ""
+        }
+    );
+    (minimal_hs, truncated)
+}
+
+/// Finds the nodes contained in every minimum hitting set (essential) and
+/// those contained in at least one (optional), given the minimum size `opt`.
+///
+/// The optional nodes include the essential ones. This enumerates all
+/// minimum hitting sets and can thus be slow.
+pub fn classify_optimal_nodes(instance: &mut Instance, opt: usize) -> (Vec<NodeIdx>, Vec<NodeIdx>) {
+    let minimum_hs: Vec<_> = enumerate_minimal_hitting_sets(instance, opt, None)
+        .0
+        .into_iter()
+        .filter(|hs| hs.len() == opt)
+        .collect();
+    let mut num_occurrences = vec![0; instance.num_nodes_total()];
+    for &node in minimum_hs.iter().flatten() {
+        num_occurrences[node.idx()] += 1;
+    }
+
+    let optional: Vec<_> = (0..instance.num_nodes_total())
+        .filter(|&idx| num_occurrences[idx] > 0)
+        .map(NodeIdx::from)
+        .collect();
+    let essential: Vec<_> = optional
+        .iter()
+        .copied()
+        .filter(|node| num_occurrences[node.idx()] == minimum_hs.len())
+        .collect();
+    info!(
+        "{} of {} nodes in {} minimum hitting sets are essential",
+        essential.len(),
+        optional.len(),
+        minimum_hs.len()
+    );
+    (essential, optional)
+}
+
+/// Number of nodes contained in exactly one of two sorted hitting sets.
+fn symmetric_difference_size(hs1: &[NodeIdx], hs2: &[NodeIdx]) -> usize {
+    let (mut idx1, mut idx2, mut num_common) = (0, 0, 0);
+    while idx1 < hs1.len() && idx2 < hs2.len() {
+        match hs1[idx1].cmp(&hs2[idx2]) {
+            Ordering::Less => idx1 += 1,
+            Ordering::Greater => idx2 += 1,
+            Ordering::Equal => {
+                num_common += 1;
+                idx1 += 1;
+                idx2 += 1;
+            }
+        }
+    }
+    hs1.len() + hs2.len() - 2 * num_common
+}
+
+/// Selects up to `count` minimum hitting sets that differ from each other as
+/// much as possible, given the minimum size `opt`.
+///
+/// This enumerates all minimum hitting sets and then greedily picks the one
+/// farthest from those already picked, measured by the number of nodes in
+/// which two hitting sets differ.
+pub fn diverse_minimum_hitting_sets(
+    instance: &mut Instance,
+    opt: usize,
+    count: usize,
+) -> Vec<Vec<NodeIdx>> {
+    let mut candidates: Vec<_> = enumerate_minimal_hitting_sets(instance, opt, None)
+        .0
+        .into_iter()
+        .filter(|hs| hs.len() == opt)
+        .collect();
+    let num_minimum_hs = candidates.len();
+    let mut min_distances = vec![usize::MAX; candidates.len()];
+    let mut diverse = Vec::new();
+    while diverse.len() < count && !candidates.is_empty() {
+        let (best_idx, _) = min_distances
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, &distance)| distance)
+            .expect("No candidates left");
+        let hs = candidates.swap_remove(best_idx);
+        min_distances.swap_remove(best_idx);
+        for (candidate, min_distance) in candidates.iter().zip(&mut min_distances) {
+            *min_distance = (*min_distance).min(symmetric_difference_size(candidate, &hs));
+        }
+        diverse.push(hs);
+    }
+
+    info!(
+        "Selected {} of {} minimum hitting sets",
+        diverse.len(),
+        num_minimum_hs
+    );
+    diverse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::LoadOptions;
+
+    #[test]
+    fn max_count_stops_with_more_remaining() {
+        // Four disjoint edges of two nodes have 2^4 minimum hitting sets
+        let text = "8 4\n2 0 1\n2 2 3\n2 4 5\n2 6 7\n";
+        let mut instance =
+            Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+
+        let (all_hs, truncated) = enumerate_minimal_hitting_sets(&mut instance, 4, None);
+        assert_eq!(all_hs.len(), 16);
+        assert!(!truncated);
+
+        let (some_hs, truncated) = enumerate_minimal_hitting_sets(&mut instance, 4, Some(5));
+        assert_eq!(some_hs.len(), 5);
+        assert!(truncated);
+        assert!(some_hs
+            .iter()
+            .all(|hs| hs.len() == 4 && all_hs.contains(hs)));
+
+        let (exact_hs, truncated) = enumerate_minimal_hitting_sets(&mut instance, 4, Some(16));
+        assert_eq!(exact_hs.len(), 16);
+        assert!(exact_hs.iter().all(|hs| all_hs.contains(hs)));
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn diverse_hitting_sets_are_spread_out() {
+        // Every minimum hitting set picks one node of each of the three edges
+        let text = "6 3\n2 0 1\n2 2 3\n2 4 5\n";
+        let mut instance =
+            Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+
+        let diverse = diverse_minimum_hitting_sets(&mut instance, 3, 2);
+        assert_eq!(diverse.len(), 2);
+        // The second one is the complement of the first
+        assert_eq!(symmetric_difference_size(&diverse[0], &diverse[1]), 6);
+
+        let diverse = diverse_minimum_hitting_sets(&mut instance, 3, 3);
+        for (idx, hs1) in diverse.iter().enumerate() {
+            for hs2 in &diverse[idx + 1..] {
+                assert!(symmetric_difference_size(hs1, hs2) >= 2);
+            }
+        }
+
+        let diverse = diverse_minimum_hitting_sets(&mut instance, 3, 20);
+        assert_eq!(diverse.len(), 8);
+    }
+
+    #[test]
+    fn sink_sees_each_hitting_set_once_and_can_stop() {
+        // Minimal hitting sets of the path are {0, 2}, {1, 2} and {1, 3}
+        let text = "4 3\n2 0 1\n2 1 2\n2 2 3\n";
+        let mut instance =
+            Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+
+        let mut found = Vec::new();
+        let stopped = for_each_minimal_hitting_set(&mut instance, 2, |hs| {
+            found.push(hs.to_vec());
+            ControlFlow::Continue(())
+        });
+        assert!(!stopped);
+        found.sort_unstable();
+        let nodes = |raw: [usize; 2]| raw.map(NodeIdx::from).to_vec();
+        assert_eq!(found, [nodes([0, 2]), nodes([1, 2]), nodes([1, 3])]);
+
+        let mut num_calls = 0;
+        let stopped = for_each_minimal_hitting_set(&mut instance, 2, |_| {
+            num_calls += 1;
+            ControlFlow::Break(())
+        });
+        assert!(stopped);
+        assert_eq!(num_calls, 1);
+    }
+}
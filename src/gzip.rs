@@ -0,0 +1,412 @@
+use anyhow::{anyhow, bail, ensure, Result};
+use std::io::{BufRead, Cursor, Read};
+
+pub const MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+const METHOD_DEFLATE: u8 = 8;
+const FLAG_HEADER_CRC: u8 = 1 << 1;
+const FLAG_EXTRA: u8 = 1 << 2;
+const FLAG_NAME: u8 = 1 << 3;
+const FLAG_COMMENT: u8 = 1 << 4;
+
+/// Base lengths and number of extra bits of the length symbols 257 to 285
+const LENGTH_BASES: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances and number of extra bits of the distance symbols
+const DISTANCE_BASES: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which the code lengths of the code length alphabet are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses the reader if it starts with the gzip magic bytes and passes
+/// it through unchanged otherwise. Only peeks at the buffered bytes, so the
+/// magic bytes are not consumed.
+pub fn decompress_if_gzip<'a>(mut reader: Box<dyn BufRead + 'a>) -> Result<Box<dyn BufRead + 'a>> {
+    if !reader.fill_buf()?.starts_with(&MAGIC_BYTES) {
+        return Ok(reader);
+    }
+    let mut compressed = Vec::new();
+    reader.read_to_end(&mut compressed)?;
+    Ok(Box::new(Cursor::new(decompress(&compressed)?)))
+}
+
+/// Decompresses a complete gzip stream, checking the length and checksum of
+/// each member. Concatenated members are decompressed one after the other.
+pub fn decompress(mut data: &[u8]) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    while !data.is_empty() {
+        let member_start = output.len();
+        let mut bits = BitReader::new(&data[header_len(data)?..]);
+        inflate(&mut bits, &mut output, member_start)?;
+        let trailer = bits.bytes(8)?;
+        let member = &output[member_start..];
+        ensure!(
+            crc32(member).to_le_bytes() == trailer[..4],
+            "gzip checksum does not match"
+        );
+        ensure!(
+            (member.len() as u32).to_le_bytes() == trailer[4..],
+            "gzip length does not match"
+        );
+        data = bits.rest();
+    }
+    Ok(output)
+}
+
+/// Length of the gzip member header at the start of `data`.
+fn header_len(data: &[u8]) -> Result<usize> {
+    ensure!(
+        data.len() >= 10 && data.starts_with(&MAGIC_BYTES),
+        "not a gzip stream"
+    );
+    ensure!(
+        data[2] == METHOD_DEFLATE,
+        "unsupported gzip compression method {}",
+        data[2]
+    );
+    let truncated = || anyhow!("gzip header is truncated");
+    let flags = data[3];
+    let mut len = 10;
+    if flags & FLAG_EXTRA != 0 {
+        let extra_len = data.get(len..len + 2).ok_or_else(truncated)?;
+        len += 2 + usize::from(u16::from_le_bytes([extra_len[0], extra_len[1]]));
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            // Zero-terminated string
+            let string_len = data
+                .get(len..)
+                .and_then(|rest| rest.iter().position(|&byte| byte == 0))
+                .ok_or_else(truncated)?;
+            len += string_len + 1;
+        }
+    }
+    if flags & FLAG_HEADER_CRC != 0 {
+        len += 2;
+    }
+    ensure!(len <= data.len(), truncated());
+    Ok(len)
+}
+
+/// Reads the bits of a DEFLATE stream, starting with the least significant
+/// bit of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            bit: 0,
+        }
+    }
+
+    fn bits(&mut self, num_bits: u8) -> Result<usize> {
+        let mut value = 0;
+        for idx in 0..num_bits {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| anyhow!("deflate stream is truncated"))?;
+            value |= usize::from((byte >> self.bit) & 1) << idx;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Skips to the next byte boundary and reads whole bytes from there.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| anyhow!("deflate stream is truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Bytes after the current one.
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+}
+
+/// Canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    /// Number of codes of each length
+    counts: [usize; 16],
+
+    /// Symbols ordered by their codes
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self> {
+        let mut counts = [0; 16];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+
+        // Incomplete codes are allowed, e.g. a single distance code
+        let mut num_unused = 1_usize;
+        for &count in &counts[1..] {
+            num_unused = (num_unused << 1)
+                .checked_sub(count)
+                .ok_or_else(|| anyhow!("huffman code lengths are oversubscribed"))?;
+        }
+
+        let mut offsets = [0; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; counts.iter().sum()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len > 0 {
+                let offset = &mut offsets[usize::from(len)];
+                symbols[*offset] = symbol;
+                *offset += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<usize> {
+        // First code of the current length and index of its symbol
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for &count in &self.counts[1..] {
+            code |= bits.bits(1)?;
+            if code < first + count {
+                return Ok(self.symbols[index + code - first]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        bail!("invalid huffman code")
+    }
+}
+
+/// Decompresses the DEFLATE blocks of one gzip member, appending them to
+/// `output`. Back references can reach back to `member_start`.
+fn inflate(bits: &mut BitReader, output: &mut Vec<u8>, member_start: usize) -> Result<()> {
+    loop {
+        let is_last = bits.bits(1)? == 1;
+        match bits.bits(2)? {
+            0 => {
+                let header = bits.bytes(4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                ensure!(
+                    len == !u16::from_le_bytes([header[2], header[3]]),
+                    "stored deflate block has an invalid length"
+                );
+                output.extend_from_slice(bits.bytes(usize::from(len))?);
+            }
+            1 => {
+                let (literals, distances) = fixed_codes()?;
+                inflate_block(bits, output, member_start, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(bits)?;
+                inflate_block(bits, output, member_start, &literals, &distances)?;
+            }
+            _ => bail!("invalid deflate block type"),
+        }
+        if is_last {
+            return Ok(());
+        }
+    }
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman)> {
+    let mut lengths = [8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(bits: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let num_literals = bits.bits(5)? + 257;
+    let num_distances = bits.bits(5)? + 1;
+    let num_code_lengths = bits.bits(4)? + 4;
+    let mut code_lengths = [0; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..num_code_lengths] {
+        code_lengths[symbol] = bits.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let num_lengths = num_literals + num_distances;
+    let mut lengths = Vec::with_capacity(num_lengths);
+    while lengths.len() < num_lengths {
+        let (len, repetitions) = match code_lengths.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = lengths
+                    .last()
+                    .copied()
+                    .ok_or_else(|| anyhow!("no code length to repeat"))?;
+                (previous, 3 + bits.bits(2)?)
+            }
+            17 => (0, 3 + bits.bits(3)?),
+            _ => (0, 11 + bits.bits(7)?),
+        };
+        lengths.resize(lengths.len() + repetitions, len);
+    }
+    ensure!(
+        lengths.len() == num_lengths,
+        "code lengths exceed the number of symbols"
+    );
+    ensure!(lengths[256] > 0, "deflate block has no end code");
+    Ok((
+        Huffman::new(&lengths[..num_literals])?,
+        Huffman::new(&lengths[num_literals..])?,
+    ))
+}
+
+fn inflate_block(
+    bits: &mut BitReader,
+    output: &mut Vec<u8>,
+    member_start: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<()> {
+    loop {
+        let symbol = literals.decode(bits)?;
+        if symbol < 256 {
+            output.push(symbol as u8);
+            continue;
+        } else if symbol == 256 {
+            return Ok(());
+        }
+
+        let length_idx = symbol - 257;
+        ensure!(length_idx < LENGTH_BASES.len(), "invalid length symbol");
+        let len = LENGTH_BASES[length_idx] + bits.bits(LENGTH_EXTRA_BITS[length_idx])?;
+        let distance_idx = distances.decode(bits)?;
+        ensure!(
+            distance_idx < DISTANCE_BASES.len(),
+            "invalid distance symbol"
+        );
+        let distance =
+            DISTANCE_BASES[distance_idx] + bits.bits(DISTANCE_EXTRA_BITS[distance_idx])?;
+        ensure!(
+            distance <= output.len() - member_start,
+            "distance reaches before the start of the data"
+        );
+        // Byte by byte, since the copy can overlap with itself
+        for _ in 0..len {
+            output.push(output[output.len() - distance]);
+        }
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETERSEN: &str = "10 15\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n2 0 5\n2 1 6\n2 2 7\n\
+                            2 3 8\n2 4 9\n2 5 7\n2 6 8\n2 7 9\n2 5 8\n2 6 9\n";
+
+    /// `PETERSEN` compressed with dynamic huffman codes
+    const PETERSEN_DYNAMIC: [u8; 71] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x2d, 0xc9, 0xc9, 0x0d, 0x00,
+        0x20, 0x0c, 0x03, 0xc1, 0x3f, 0x55, 0xb8, 0x04, 0x12, 0xc8, 0x41, 0xff, 0x8d, 0x61, 0x19,
+        0x3e, 0x63, 0x69, 0x6d, 0x13, 0x16, 0xc3, 0xc1, 0xa1, 0x06, 0xa7, 0x8e, 0x45, 0x17, 0xb6,
+        0xfa, 0x33, 0xf4, 0xa6, 0xde, 0xd2, 0xdb, 0x74, 0xe3, 0xd0, 0x50, 0x49, 0x95, 0xfa, 0xa5,
+        0x55, 0xce, 0xb8, 0xc9, 0xbc, 0xfb, 0x35, 0x60, 0x00, 0x00, 0x00,
+    ];
+
+    const PATH: &str = "3 2\n2 0 1\n2 1 2\n";
+
+    /// `PATH` compressed with the fixed huffman codes
+    const PATH_FIXED: [u8; 33] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x33, 0x56, 0x30, 0xe2, 0x32,
+        0x52, 0x30, 0x50, 0x30, 0x04, 0x92, 0x86, 0x40, 0x36, 0x00, 0x76, 0xa4, 0xde, 0x40, 0x10,
+        0x00, 0x00, 0x00,
+    ];
+
+    /// `PATH` in a stored block without compression
+    const PATH_STORED: [u8; 39] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x03, 0x01, 0x10, 0x00, 0xef, 0xff,
+        0x33, 0x20, 0x32, 0x0a, 0x32, 0x20, 0x30, 0x20, 0x31, 0x0a, 0x32, 0x20, 0x31, 0x20, 0x32,
+        0x0a, 0x76, 0xa4, 0xde, 0x40, 0x10, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn decompresses_all_block_types() {
+        for (compressed, text) in [
+            (&PETERSEN_DYNAMIC[..], PETERSEN),
+            (&PATH_FIXED[..], PATH),
+            (&PATH_STORED[..], PATH),
+        ] {
+            assert_eq!(decompress(compressed).unwrap(), text.as_bytes());
+        }
+
+        let concatenated = [&PATH_FIXED[..], &PETERSEN_DYNAMIC[..]].concat();
+        let text = format!("{PATH}{PETERSEN}");
+        assert_eq!(decompress(&concatenated).unwrap(), text.as_bytes());
+    }
+
+    #[test]
+    fn rejects_corrupted_streams() {
+        let mut corrupted = PATH_STORED;
+        corrupted[20] ^= 1;
+        assert!(decompress(&corrupted).is_err());
+        assert!(decompress(&PETERSEN_DYNAMIC[..40]).is_err());
+    }
+
+    #[test]
+    fn detects_gzip_by_magic_bytes() {
+        let read = |data: &[u8]| {
+            let mut text = String::new();
+            decompress_if_gzip(Box::new(data))
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            text
+        };
+        assert_eq!(read(&PETERSEN_DYNAMIC), PETERSEN);
+        assert_eq!(read(PATH.as_bytes()), PATH);
+    }
+}
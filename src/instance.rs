@@ -1,13 +1,17 @@
 use crate::{
     create_idx_struct,
     data_structures::{cont_idx_vec::ContiguousIdxVec, skipvec::SkipVec},
-    small_indices::SmallIdx,
+    small_indices::{IdxHashSet, SmallIdx},
 };
-use anyhow::{anyhow, ensure, Error, Result};
-use log::{info, trace};
+use anyhow::{anyhow, ensure, Context, Result};
+use log::{info, trace, warn};
+use rustc_hash::{FxHashMap, FxHasher};
 use serde::Deserialize;
 use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
     fmt::{self, Display, Write as _},
+    hash::{Hash, Hasher},
     io::{BufRead, Write},
     mem,
     time::Instant,
@@ -17,6 +21,37 @@ create_idx_struct!(pub NodeIdx);
 create_idx_struct!(pub EdgeIdx);
 create_idx_struct!(pub EntryIdx);
 
+// The solver runs on a single thread, so a thread-local counter avoids atomic
+// operations and keeps concurrent solves, such as tests, apart
+#[cfg(feature = "count-traversals")]
+thread_local! {
+    static INCIDENCE_TRAVERSALS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Counts a visited incidence entry, if the `count-traversals` feature is
+/// enabled.
+#[inline]
+fn count_traversal<T>(item: T) -> T {
+    #[cfg(feature = "count-traversals")]
+    INCIDENCE_TRAVERSALS.with(|count| count.set(count.get() + 1));
+    item
+}
+
+/// Number of incidence entries visited through `Instance::edge` and
+/// `Instance::node` so far on the current thread. Only counted with the
+/// `count-traversals` feature.
+#[cfg(feature = "count-traversals")]
+#[allow(clippy::unnecessary_wraps)]
+pub fn incidence_traversals() -> Option<usize> {
+    Some(INCIDENCE_TRAVERSALS.with(std::cell::Cell::get))
+}
+
+#[cfg(not(feature = "count-traversals"))]
+#[allow(clippy::unnecessary_wraps)]
+pub fn incidence_traversals() -> Option<usize> {
+    None
+}
+
 #[derive(Debug)]
 struct CompressedIlpName<T>(T);
 
@@ -32,33 +67,272 @@ impl<T: SmallIdx> Display for CompressedIlpName<T> {
     }
 }
 
+/// Parses a non-negative integer, accepting the same tokens as `str::parse`.
+/// This is done by hand since the generic implementation is noticeably slower
+/// when loading large instances.
+fn parse_number(token: &str) -> Result<usize> {
+    let digits = token.strip_prefix('+').unwrap_or(token).as_bytes();
+    let number = if digits.is_empty() {
+        None
+    } else {
+        digits.iter().try_fold(0_usize, |number, &digit| {
+            if digit.is_ascii_digit() {
+                number
+                    .checked_mul(10)?
+                    .checked_add(usize::from(digit - b'0'))
+            } else {
+                None
+            }
+        })
+    };
+    number.ok_or_else(|| anyhow!("expected non-negative integer, found '{}'", token))
+}
+
+/// Reads the lines of the text-based format while keeping track of the line
+/// number. If comments are enabled, lines starting with `#` are skipped and
+/// comments of the form `# key: value` are collected as metadata. A `# nodes:`
+/// comment starts a section of comments holding one node label each, which
+/// ends at the next line that is not a comment.
+#[derive(Debug)]
+struct TextLines<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    comments: bool,
+    metadata: HashMap<String, String>,
+    node_labels: Option<Vec<String>>,
+    reading_node_labels: bool,
+}
+
+impl<R: BufRead> TextLines<R> {
+    fn new(reader: R, comments: bool) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+            comments,
+            metadata: HashMap::new(),
+            node_labels: None,
+            reading_node_labels: false,
+        }
+    }
+
+    /// Reads the next line that is not a comment, returning `false` at the
+    /// end of the input.
+    fn advance(&mut self) -> Result<bool> {
+        loop {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(false);
+            }
+            self.line_number += 1;
+            if !self.comments {
+                return Ok(true);
+            }
+
+            let Some(comment) = self.line.trim_start().strip_prefix('#') else {
+                self.reading_node_labels = false;
+                return Ok(true);
+            };
+            if self.reading_node_labels {
+                self.node_labels
+                    .get_or_insert_with(Vec::new)
+                    .push(comment.trim().to_string());
+            } else if comment.trim() == "nodes:" {
+                self.reading_node_labels = true;
+                self.node_labels.get_or_insert_with(Vec::new);
+            } else if let Some((key, value)) = comment.split_once(':') {
+                let key = key.trim();
+                if !key.is_empty() && !key.contains(char::is_whitespace) {
+                    self.metadata
+                        .insert(key.to_string(), value.trim().to_string());
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct ParsedEdgeHandler {
     edge_incidences: Vec<SkipVec<(NodeIdx, EntryIdx)>>,
     node_degrees: Vec<usize>,
+    max_edge_degree: Option<usize>,
+    drop_large_edges: bool,
+    num_dropped_edges: usize,
+
+    /// Indices of the edges read so far, grouped by the hash of their nodes.
+    /// Only used if duplicate edges are skipped.
+    edges_by_hash: Option<FxHashMap<u64, Vec<usize>>>,
+    num_duplicate_edges: usize,
+
+    /// Number of edges that listed a node more than once, which is only kept
+    /// once.
+    num_edges_with_repeated_nodes: usize,
+
+    /// Number of edges read so far, including skipped ones, and the input
+    /// index of each kept edge.
+    num_read_edges: usize,
+    input_edge_indices: Vec<usize>,
 }
 
 impl ParsedEdgeHandler {
+    /// Checks whether an edge with the same nodes was read before, and
+    /// remembers the given edge otherwise.
+    fn is_duplicate(&mut self, incidences: &SkipVec<(NodeIdx, EntryIdx)>) -> bool {
+        let Some(edges_by_hash) = &mut self.edges_by_hash else {
+            return false;
+        };
+        let nodes = || incidences.iter().map(|(_, (node, _))| *node);
+        let mut hasher = FxHasher::default();
+        for node in nodes() {
+            node.hash(&mut hasher);
+        }
+        let same_hash = edges_by_hash.entry(hasher.finish()).or_default();
+        let edge_incidences = &self.edge_incidences;
+        if same_hash.iter().any(|&edge_idx| {
+            edge_incidences[edge_idx]
+                .iter()
+                .map(|(_, (node, _))| *node)
+                .eq(nodes())
+        }) {
+            return true;
+        }
+        same_hash.push(edge_incidences.len());
+        false
+    }
+
     fn handle_edge(&mut self, node_indices: impl IntoIterator<Item = Result<usize>>) -> Result<()> {
-        let incidences = SkipVec::try_sorted_from(node_indices.into_iter().map(|idx_result| {
-            idx_result.and_then(|node_idx| {
+        let mut incidences =
+            SkipVec::try_sorted_from(node_indices.into_iter().map(|idx_result| {
+                idx_result.and_then(|node_idx| {
+                    ensure!(
+                        node_idx < self.node_degrees.len(),
+                        "invalid node idx in edge: {} (instance has {} nodes)",
+                        node_idx,
+                        self.node_degrees.len()
+                    );
+                    Ok((NodeIdx::from(node_idx), EntryIdx::INVALID))
+                })
+            }))?;
+        ensure!(incidences.len() > 0, "edges may not be empty");
+        let input_edge_idx = self.num_read_edges;
+        self.num_read_edges += 1;
+        let mut prev_node = None;
+        if incidences
+            .iter()
+            .any(|(_, &(node, _))| prev_node.replace(node) == Some(node))
+        {
+            let mut entries: Vec<_> = incidences.iter().map(|(_, &entry)| entry).collect();
+            entries.dedup();
+            incidences = entries.into_iter().collect();
+            self.num_edges_with_repeated_nodes += 1;
+        }
+        if let Some(max_edge_degree) = self.max_edge_degree {
+            if incidences.len() > max_edge_degree {
                 ensure!(
-                    node_idx < self.node_degrees.len(),
-                    "invalid node idx in edge: {}",
-                    node_idx
+                    self.drop_large_edges,
+                    "edge has {} nodes, more than the maximum of {}",
+                    incidences.len(),
+                    max_edge_degree
                 );
-                Ok((NodeIdx::from(node_idx), EntryIdx::INVALID))
-            })
-        }))?;
-        ensure!(incidences.len() > 0, "edges may not be empty");
+                self.num_dropped_edges += 1;
+                return Ok(());
+            }
+        }
+        if self.is_duplicate(&incidences) {
+            self.num_duplicate_edges += 1;
+            return Ok(());
+        }
         for (_, (node, _)) in &incidences {
             self.node_degrees[node.idx()] += 1;
         }
         self.edge_incidences.push(incidences);
+        self.input_edge_indices.push(input_edge_idx);
         Ok(())
     }
 }
 
+/// Options for parsing the text-based hypergraph format.
+///
+/// Each flag corresponds to one independent command line switch and any
+/// combination of them is valid, so they are kept as plain booleans.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct LoadOptions {
+    /// Edge lines only contain the nodes of the edge, without its size as prefix.
+    pub no_degree_prefix: bool,
+
+    /// Ignore any lines following the declared number of edges instead of
+    /// treating them as an error.
+    pub ignore_trailing_lines: bool,
+
+    /// Read everything after the first line as a single stream of numbers in
+    /// which edges are terminated by this value instead of by line breaks.
+    /// Edges then have no degree prefix and the value itself cannot be used
+    /// as a node index.
+    pub edge_separator: Option<usize>,
+
+    /// Node indices start at 1 instead of 0.
+    pub one_indexed: bool,
+
+    /// Skip lines starting with `#`, collecting those of the form
+    /// `# key: value` as metadata and those after `# nodes:` as node labels.
+    pub comments: bool,
+
+    /// Ignore any numbers in the first line after the node and edge count.
+    pub ignore_extra_header: bool,
+
+    /// The input has no first line with the node and edge count. Instead, all
+    /// lines are edges and the number of nodes is one more than the largest
+    /// node index. Not supported together with `edge_separator`.
+    pub infer_node_count: bool,
+
+    /// Edges with more nodes than this are rejected, or dropped if
+    /// `drop_large_edges` is set. Dropping edges relaxes the problem, so a
+    /// hitting set of the result may miss the dropped edges.
+    pub max_edge_degree: Option<usize>,
+
+    /// Drop edges above `max_edge_degree` instead of treating them as an error.
+    pub drop_large_edges: bool,
+
+    /// Only keep the first of several edges with the same nodes.
+    pub skip_duplicate_edges: bool,
+}
+
+impl LoadOptions {
+    /// Parses the node indices of an edge line, skipping the degree prefix if
+    /// present.
+    fn edge_line_nodes<'a>(
+        &'a self,
+        line: &'a str,
+    ) -> Result<impl Iterator<Item = Result<usize>> + 'a> {
+        let mut numbers = line.split_ascii_whitespace().map(parse_number);
+        if !self.no_degree_prefix {
+            // Skip degree
+            numbers
+                .next()
+                .ok_or_else(|| anyhow!("empty edge line in input, expected degree"))??;
+        }
+        Ok(numbers.map(|idx| idx.and_then(|idx| self.node_idx(idx))))
+    }
+
+    fn node_idx(&self, idx: usize) -> Result<usize> {
+        if self.one_indexed {
+            idx.checked_sub(1)
+                .ok_or_else(|| anyhow!("invalid node idx in one-indexed edge: 0"))
+        } else {
+            Ok(idx)
+        }
+    }
+}
+
+/// Options for writing the text-based hypergraph format.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Write node indices starting at 1 instead of 0.
+    pub one_indexed: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct JsonInstance {
     num_nodes: usize,
@@ -71,28 +345,101 @@ pub struct Instance {
     edges: ContiguousIdxVec<EdgeIdx>,
     node_incidences: Vec<SkipVec<(EdgeIdx, EntryIdx)>>,
     edge_incidences: Vec<SkipVec<(NodeIdx, EntryIdx)>>,
+
+    /// Number of alive nodes for each degree, used to keep track of the
+    /// maximum node degree without scanning all nodes.
+    node_degree_counts: Vec<usize>,
+    max_node_degree: usize,
+
+    metadata: HashMap<String, String>,
+    node_labels: Option<Vec<String>>,
+
+    /// Index of each edge in the input, only kept if edges were skipped
+    /// while loading so that the two differ.
+    input_edge_indices: Option<Vec<usize>>,
+    num_input_edges: usize,
 }
 
 impl Instance {
     fn load(
         num_nodes: usize,
         num_edges: usize,
+        options: &LoadOptions,
         read_edges: impl FnOnce(&mut ParsedEdgeHandler) -> Result<()>,
     ) -> Result<Self> {
+        ensure!(
+            num_nodes > 0 || num_edges == 0,
+            "instance has {} edges but no nodes, so every edge would be empty",
+            num_edges
+        );
+        // The largest index is reserved for the INVALID sentinel
+        ensure!(
+            num_nodes <= NodeIdx::INVALID.idx(),
+            "instance has {} nodes, more than the supported maximum of {}",
+            num_nodes,
+            NodeIdx::INVALID.idx()
+        );
+        ensure!(
+            num_edges <= EdgeIdx::INVALID.idx(),
+            "instance has {} edges, more than the supported maximum of {}",
+            num_edges,
+            EdgeIdx::INVALID.idx()
+        );
         let mut handler = ParsedEdgeHandler {
             edge_incidences: Vec::with_capacity(num_edges),
             node_degrees: vec![0; num_nodes],
+            max_edge_degree: options.max_edge_degree,
+            drop_large_edges: options.drop_large_edges,
+            num_dropped_edges: 0,
+            edges_by_hash: options.skip_duplicate_edges.then(FxHashMap::default),
+            num_duplicate_edges: 0,
+            num_edges_with_repeated_nodes: 0,
+            num_read_edges: 0,
+            input_edge_indices: Vec::with_capacity(num_edges),
         };
         read_edges(&mut handler)?;
         let ParsedEdgeHandler {
             mut edge_incidences,
             node_degrees,
+            num_dropped_edges,
+            num_duplicate_edges,
+            num_edges_with_repeated_nodes,
+            num_read_edges,
+            input_edge_indices,
+            ..
         } = handler;
+        if num_edges_with_repeated_nodes > 0 {
+            warn!(
+                "Removed repeated nodes from {} edges",
+                num_edges_with_repeated_nodes
+            );
+        }
+        if num_dropped_edges > 0 {
+            info!(
+                "Dropped {} edges above the maximum degree",
+                num_dropped_edges
+            );
+        }
+        if num_duplicate_edges > 0 {
+            info!("Skipped {} duplicate edges", num_duplicate_edges);
+        }
+        let input_edge_indices = (num_dropped_edges + num_duplicate_edges > 0).then(|| {
+            // Capacity was reserved for the skipped edges as well
+            edge_incidences.shrink_to_fit();
+            input_edge_indices
+        });
+        let num_edges = edge_incidences.len();
 
         let mut node_incidences: Vec<_> = node_degrees
             .iter()
             .map(|&len| SkipVec::with_len(len))
             .collect();
+        let mut node_degree_counts = vec![0; num_edges + 1];
+        for &degree in &node_degrees {
+            node_degree_counts[degree] += 1;
+        }
+        let max_node_degree = node_degrees.iter().copied().max().unwrap_or(0);
+
         let mut rem_node_degrees = node_degrees;
         for (edge, incidences) in edge_incidences.iter_mut().enumerate() {
             let edge = EdgeIdx::from(edge);
@@ -110,47 +457,178 @@ impl Instance {
             edges: (0..num_edges).map(EdgeIdx::from).collect(),
             node_incidences,
             edge_incidences,
+            node_degree_counts,
+            max_node_degree,
+            metadata: HashMap::new(),
+            node_labels: None,
+            input_edge_indices,
+            num_input_edges: num_read_edges,
         })
     }
 
-    pub fn load_from_text(mut reader: impl BufRead) -> Result<Self> {
+    fn set_comment_data(&mut self, lines: TextLines<impl BufRead>) -> Result<()> {
+        if let Some(node_labels) = &lines.node_labels {
+            ensure!(
+                node_labels.len() == self.num_nodes_total(),
+                "found {} node labels for {} nodes",
+                node_labels.len(),
+                self.num_nodes_total()
+            );
+        }
+        self.metadata = lines.metadata;
+        self.node_labels = lines.node_labels;
+        Ok(())
+    }
+
+    pub fn load_from_text(reader: impl BufRead, options: &LoadOptions) -> Result<Self> {
         let time_before = Instant::now();
-        let mut line = String::new();
+        let mut lines = TextLines::new(reader, options.comments);
+
+        if options.infer_node_count {
+            return Self::load_from_headerless_text(lines, options);
+        }
 
-        reader.read_line(&mut line)?;
-        let mut numbers = line.split_ascii_whitespace().map(str::parse);
+        lines.advance()?;
+        let mut numbers = lines.line.split_ascii_whitespace().map(parse_number);
         let num_nodes = numbers
             .next()
-            .ok_or_else(|| anyhow!("Missing node count"))??;
+            .ok_or_else(|| anyhow!("Missing node count"))?
+            .with_context(|| format!("in line {}", lines.line_number))?;
         let num_edges = numbers
             .next()
-            .ok_or_else(|| anyhow!("Missing edge count"))??;
+            .ok_or_else(|| anyhow!("Missing edge count"))?
+            .with_context(|| format!("in line {}", lines.line_number))?;
         ensure!(
-            numbers.next().is_none(),
+            options.ignore_extra_header || numbers.next().is_none(),
             "Too many numbers in first input line"
         );
 
-        let instance = Self::load(num_nodes, num_edges, |handler| {
-            for _ in 0..num_edges {
-                line.clear();
-                reader.read_line(&mut line)?;
-                let mut numbers = line
-                    .split_ascii_whitespace()
-                    .map(|s| s.parse::<usize>().map_err(Error::from));
-                // Skip degree
-                numbers
-                    .next()
-                    .ok_or_else(|| anyhow!("empty edge line in input, expected degree"))??;
-                handler.handle_edge(numbers)?;
+        let mut instance = Self::load(num_nodes, num_edges, options, |handler| {
+            if let Some(separator) = options.edge_separator {
+                let mut num_read_edges = 0;
+                let mut edge = Vec::new();
+                while lines.advance()? {
+                    let line_number = lines.line_number;
+                    for token in lines.line.split_ascii_whitespace() {
+                        let number = parse_number(token)
+                            .with_context(|| format!("in line {line_number}"))?;
+                        if number != separator {
+                            edge.push(number);
+                            continue;
+                        }
+                        ensure!(
+                            num_read_edges < num_edges,
+                            "expected {} edges, found more",
+                            num_edges
+                        );
+                        handler
+                            .handle_edge(edge.drain(..).map(|idx| options.node_idx(idx)))
+                            .with_context(|| format!("in line {line_number}"))?;
+                        num_read_edges += 1;
+                    }
+                }
+                ensure!(
+                    edge.is_empty(),
+                    "last edge is not terminated by separator {}",
+                    separator
+                );
+                ensure!(
+                    num_read_edges == num_edges,
+                    "expected {} edges, found {}",
+                    num_edges,
+                    num_read_edges
+                );
+                return Ok(());
+            }
+
+            for num_read_edges in 0..num_edges {
+                ensure!(
+                    lines.advance()?,
+                    "expected {} edges, found {}",
+                    num_edges,
+                    num_read_edges
+                );
+                let line_number = lines.line_number;
+                let node_indices = options
+                    .edge_line_nodes(&lines.line)
+                    .with_context(|| format!("in line {line_number}"))?;
+                handler
+                    .handle_edge(node_indices)
+                    .with_context(|| format!("in line {line_number}"))?;
+            }
+
+            if !options.ignore_trailing_lines {
+                let mut num_trailing_edges = 0;
+                while lines.advance()? {
+                    if !lines.line.trim().is_empty() {
+                        num_trailing_edges += 1;
+                    }
+                }
+                ensure!(
+                    num_trailing_edges == 0,
+                    "expected {} edges, found {}",
+                    num_edges,
+                    num_edges + num_trailing_edges
+                );
             }
 
             Ok(())
         })?;
+        instance.set_comment_data(lines)?;
 
         info!(
-            "Loaded text instance with {} nodes, {} edges in {:.2?}",
+            "Loaded text instance with {} nodes, {} edges, {} incidences in {:.2?}",
             num_nodes,
-            num_edges,
+            instance.num_edges(),
+            instance.num_incidences(),
+            time_before.elapsed(),
+        );
+        Ok(instance)
+    }
+
+    fn load_from_headerless_text(
+        mut lines: TextLines<impl BufRead>,
+        options: &LoadOptions,
+    ) -> Result<Self> {
+        ensure!(
+            options.edge_separator.is_none(),
+            "inferring the node count is not supported with an edge separator"
+        );
+        let time_before = Instant::now();
+        let mut edges = Vec::new();
+        while lines.advance()? {
+            if lines.line.trim().is_empty() {
+                continue;
+            }
+            let line_number = lines.line_number;
+            let edge: Vec<_> = options
+                .edge_line_nodes(&lines.line)
+                .and_then(Iterator::collect)
+                .with_context(|| format!("in line {line_number}"))?;
+            edges.push((line_number, edge));
+        }
+
+        let num_nodes = edges
+            .iter()
+            .flat_map(|(_, edge)| edge.iter().copied())
+            .max()
+            .map_or(0, |max_node| max_node + 1);
+        let num_edges = edges.len();
+        let mut instance = Self::load(num_nodes, num_edges, options, |handler| {
+            for (line_number, edge) in edges {
+                handler
+                    .handle_edge(edge.into_iter().map(Ok))
+                    .with_context(|| format!("in line {line_number}"))?;
+            }
+            Ok(())
+        })?;
+        instance.set_comment_data(lines)?;
+
+        info!(
+            "Loaded headerless text instance with {} nodes, {} edges, {} incidences in {:.2?}",
+            num_nodes,
+            instance.num_edges(),
+            instance.num_incidences(),
             time_before.elapsed(),
         );
         Ok(instance)
@@ -165,22 +643,168 @@ impl Instance {
         let JsonInstance { num_nodes, edges } = serde_json::from_str(&text)?;
 
         let num_edges = edges.len();
-        let instance = Self::load(num_nodes, num_edges, |handler| {
-            for edge in edges {
-                handler.handle_edge(edge.into_iter().map(Ok))?;
+        let instance = Self::load(num_nodes, num_edges, &LoadOptions::default(), |handler| {
+            for (edge_idx, edge) in edges.into_iter().enumerate() {
+                handler
+                    .handle_edge(edge.into_iter().map(Ok))
+                    .with_context(|| format!("in edge {edge_idx}"))?;
             }
             Ok(())
         })?;
 
         info!(
-            "Loaded json instance with {} nodes, {} edges in {:.2?}",
+            "Loaded json instance with {} nodes, {} edges, {} incidences in {:.2?}",
             num_nodes,
             num_edges,
+            instance.num_incidences(),
             time_before.elapsed(),
         );
         Ok(instance)
     }
 
+    /// Combines the alive edges of several instances into one instance whose
+    /// nodes are shared between all parts. The number of nodes is the
+    /// maximum over all parts. Metadata is merged, with later parts taking
+    /// precedence, and node labels are taken from the first part having them.
+    pub fn merge(parts: &[Self]) -> Result<Self> {
+        let num_nodes = parts.iter().map(Self::num_nodes_total).max().unwrap_or(0);
+        let num_edges = parts.iter().map(Self::num_edges).sum();
+        let mut instance = Self::load(num_nodes, num_edges, &LoadOptions::default(), |handler| {
+            for (part_idx, part) in parts.iter().enumerate() {
+                for edge in part.edges_sorted() {
+                    handler
+                        .handle_edge(part.edge(edge).map(|node| Ok(node.idx())))
+                        .with_context(|| format!("in edge {edge} of part {part_idx}"))?;
+                }
+            }
+            Ok(())
+        })?;
+
+        for part in parts {
+            instance.metadata.extend(part.metadata.clone());
+        }
+        if parts.iter().any(|part| part.input_edge_indices.is_some()) {
+            // Number the edges of each part after all input edges of the
+            // previous parts, including skipped ones
+            let mut input_edge_indices = Vec::with_capacity(num_edges);
+            let mut offset = 0;
+            for part in parts {
+                input_edge_indices.extend(
+                    part.edges_sorted()
+                        .into_iter()
+                        .map(|edge| offset + part.input_edge_index(edge)),
+                );
+                offset += part.num_input_edges;
+            }
+            instance.input_edge_indices = Some(input_edge_indices);
+            instance.num_input_edges = offset;
+        }
+        if let Some(node_labels) = parts.iter().find_map(|part| part.node_labels.as_ref()) {
+            ensure!(
+                node_labels.len() == num_nodes,
+                "found {} node labels for {} merged nodes",
+                node_labels.len(),
+                num_nodes
+            );
+            instance.node_labels = Some(node_labels.clone());
+        }
+
+        info!(
+            "Merged {} instances into one with {} nodes, {} edges",
+            parts.len(),
+            num_nodes,
+            num_edges
+        );
+        Ok(instance)
+    }
+
+    /// Partitions the alive edges into connected components, two edges being
+    /// connected if they share a node. Nodes without edges are ignored.
+    pub fn connected_components(&self) -> Vec<Vec<EdgeIdx>> {
+        let mut node_visited = vec![false; self.num_nodes_total()];
+        let mut edge_visited = vec![false; self.num_edges_total()];
+        let mut components = Vec::new();
+        for edge in self.edges_sorted() {
+            if edge_visited[edge.idx()] {
+                continue;
+            }
+
+            edge_visited[edge.idx()] = true;
+            let mut component = vec![edge];
+            let mut next_idx = 0;
+            while next_idx < component.len() {
+                for node in self.edge(component[next_idx]) {
+                    if node_visited[node.idx()] {
+                        continue;
+                    }
+                    node_visited[node.idx()] = true;
+                    for neighbor_edge in self.node(node) {
+                        if !edge_visited[neighbor_edge.idx()] {
+                            edge_visited[neighbor_edge.idx()] = true;
+                            component.push(neighbor_edge);
+                        }
+                    }
+                }
+                next_idx += 1;
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Creates a new instance consisting of the given alive edges and the
+    /// nodes contained in them. Also returns the original index of each node
+    /// of the new instance.
+    pub fn sub_instance(&self, edges: &[EdgeIdx]) -> Result<(Self, Vec<NodeIdx>)> {
+        let mut nodes: Vec<_> = edges.iter().flat_map(|&edge| self.edge(edge)).collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        let mut new_indices = vec![NodeIdx::INVALID; self.num_nodes_total()];
+        for (new_idx, &node) in nodes.iter().enumerate() {
+            new_indices[node.idx()] = NodeIdx::from(new_idx);
+        }
+
+        let mut instance = Self::load(
+            nodes.len(),
+            edges.len(),
+            &LoadOptions::default(),
+            |handler| {
+                for &edge in edges {
+                    handler.handle_edge(
+                        self.edge(edge)
+                            .map(|node| Ok(new_indices[node.idx()].idx())),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        instance.metadata.clone_from(&self.metadata);
+        Ok((instance, nodes))
+    }
+
+    /// Index of the edge in the input, counting edges skipped while loading.
+    pub fn input_edge_index(&self, edge: EdgeIdx) -> usize {
+        self.input_edge_indices
+            .as_ref()
+            .map_or(edge.idx(), |indices| indices[edge.idx()])
+    }
+
+    /// The edge read as the given edge of the input, or `None` if it was
+    /// skipped while loading.
+    pub fn edge_from_input_index(&self, input_idx: usize) -> Result<Option<EdgeIdx>> {
+        ensure!(
+            input_idx < self.num_input_edges,
+            "edge index {} out of bounds for {} edges",
+            input_idx,
+            self.num_input_edges
+        );
+        Ok(match &self.input_edge_indices {
+            Some(indices) => indices.binary_search(&input_idx).ok().map(EdgeIdx::from),
+            None => Some(EdgeIdx::from(input_idx)),
+        })
+    }
+
     pub fn num_edges(&self) -> usize {
         self.edges.len()
     }
@@ -189,6 +813,11 @@ impl Instance {
         self.node_incidences.len()
     }
 
+    /// Sum of the sizes of all alive edges.
+    pub fn num_incidences(&self) -> usize {
+        self.edges.iter().map(|&edge| self.edge_size(edge)).sum()
+    }
+
     pub fn num_edges_total(&self) -> usize {
         self.edge_incidences.len()
     }
@@ -201,6 +830,37 @@ impl Instance {
         self.node_incidences[node.idx()]
             .iter()
             .map(|(_, (edge, _))| *edge)
+            .map(count_traversal)
+    }
+
+    /// Like `node`, but fails instead of panicking if the node does not
+    /// exist. Meant for indices from user input.
+    pub fn try_node(
+        &self,
+        node: NodeIdx,
+    ) -> Result<impl ExactSizeIterator<Item = EdgeIdx> + Clone + '_> {
+        ensure!(
+            node.idx() < self.num_nodes_total(),
+            "node index {} out of bounds for {} nodes",
+            node,
+            self.num_nodes_total()
+        );
+        Ok(self.node(node))
+    }
+
+    /// Like `edge`, but fails instead of panicking if the edge does not
+    /// exist. Meant for indices from user input.
+    pub fn try_edge(
+        &self,
+        edge: EdgeIdx,
+    ) -> Result<impl ExactSizeIterator<Item = NodeIdx> + Clone + '_> {
+        ensure!(
+            edge.idx() < self.num_edges_total(),
+            "edge index {} out of bounds for {} edges",
+            edge,
+            self.num_edges_total()
+        );
+        Ok(self.edge(edge))
     }
 
     /// Nodes incident to an edge, sorted by increasing indices.
@@ -211,6 +871,7 @@ impl Instance {
         self.edge_incidences[edge.idx()]
             .iter()
             .map(|(_, (node, _))| *node)
+            .map(count_traversal)
     }
 
     /// Alive nodes in the instance, in arbitrary order.
@@ -223,30 +884,410 @@ impl Instance {
         &self.edges
     }
 
+    pub fn num_deleted_nodes(&self) -> usize {
+        self.nodes.num_deleted()
+    }
+
+    pub fn num_deleted_edges(&self) -> usize {
+        self.edges.num_deleted()
+    }
+
+    /// Alive nodes in the instance, sorted by increasing indices.
+    pub fn nodes_sorted(&self) -> Vec<NodeIdx> {
+        let mut nodes = self.nodes.to_vec();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Alive edges in the instance, sorted by increasing indices.
+    pub fn edges_sorted(&self) -> Vec<EdgeIdx> {
+        let mut edges = self.edges.to_vec();
+        edges.sort_unstable();
+        edges
+    }
+
     pub fn node_degree(&self, node: NodeIdx) -> usize {
         self.node_incidences[node.idx()].len()
     }
 
+    /// Maximum degree of all alive nodes, or `None` if there are none.
+    ///
+    /// This is maintained during deletions and restorations and takes
+    /// constant time.
+    pub fn max_node_degree(&self) -> Option<usize> {
+        if self.nodes.is_empty() {
+            None
+        } else {
+            Some(self.max_node_degree)
+        }
+    }
+
+    fn decrease_max_node_degree(&mut self) {
+        while self.max_node_degree > 0 && self.node_degree_counts[self.max_node_degree] == 0 {
+            self.max_node_degree -= 1;
+        }
+    }
+
+    pub fn is_edge_alive(&self, edge: EdgeIdx) -> bool {
+        !self.edges.is_deleted(edge.idx())
+    }
+
     pub fn edge_size(&self, edge: EdgeIdx) -> usize {
         self.edge_incidences[edge.idx()].len()
     }
 
+    /// Alive edges ordered by increasing current size, ties broken by index.
+    pub fn edges_sorted_by_size(&self) -> Vec<EdgeIdx> {
+        let mut edges = self.edges().to_vec();
+        edges.sort_unstable_by_key(|&edge| (self.edge_size(edge), edge));
+        edges
+    }
+
+    /// Converts raw node indices, e.g. from a user-supplied hitting set, into
+    /// node indices of this instance.
+    pub fn node_indices_from(&self, raw: &[u32]) -> Result<Vec<NodeIdx>> {
+        raw.iter()
+            .map(|&idx| {
+                ensure!(
+                    (idx as usize) < self.num_nodes_total(),
+                    "node index {} out of bounds for {} nodes",
+                    idx,
+                    self.num_nodes_total()
+                );
+                Ok(NodeIdx::from(idx))
+            })
+            .collect()
+    }
+
+    /// Metadata read from `# key: value` comments in the input file.
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Names of the nodes from a `# nodes:` comment section, indexed by node.
+    pub fn node_labels(&self) -> Option<&[String]> {
+        self.node_labels.as_deref()
+    }
+
+    /// Hitting set taking the first node of each alive edge not hit yet. It
+    /// is computed in a single pass and has at most one node per edge.
+    pub fn trivial_hitting_set(&self) -> Vec<NodeIdx> {
+        let mut is_chosen = vec![false; self.num_nodes_total()];
+        let mut hs = Vec::new();
+        for &edge in self.edges() {
+            if self.edge(edge).any(|node| is_chosen[node.idx()]) {
+                continue;
+            }
+            let node = self.edge(edge).next().expect("Empty edge in instance");
+            is_chosen[node.idx()] = true;
+            hs.push(node);
+        }
+        hs
+    }
+
+    /// Alive edges not containing any of the given nodes.
+    pub fn uncovered_edges(&self, chosen: &[NodeIdx]) -> Vec<EdgeIdx> {
+        let mut is_chosen = vec![false; self.num_nodes_total()];
+        for &node in chosen {
+            is_chosen[node.idx()] = true;
+        }
+        self.edges
+            .iter()
+            .copied()
+            .filter(|&edge| !self.edge(edge).any(|node| is_chosen[node.idx()]))
+            .collect()
+    }
+
+    /// For each of the chosen nodes, the alive edges it hits that no other
+    /// chosen node hits, i.e., the edges that would become uncovered without
+    /// it. Each node of an inclusion-minimal hitting set has at least one.
+    pub fn critical_edges(&self, chosen: &[NodeIdx]) -> Vec<Vec<EdgeIdx>> {
+        let mut hit_count = vec![0_usize; self.num_edges_total()];
+        for &node in chosen {
+            for edge in self.node(node) {
+                hit_count[edge.idx()] += 1;
+            }
+        }
+        chosen
+            .iter()
+            .map(|&node| {
+                self.node(node)
+                    .filter(|&edge| hit_count[edge.idx()] == 1)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Alive nodes all of whose incident edges have more than `threshold`
+    /// nodes. Such nodes are weak candidates for a hitting set, since each of
+    /// their edges can also be hit by many other nodes.
+    pub fn nodes_only_in_large_edges(&self, threshold: usize) -> Vec<NodeIdx> {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|&node| self.node(node).all(|edge| self.edge_size(edge) > threshold))
+            .collect()
+    }
+
+    /// Whether the instance is an ordinary graph, i.e., all alive edges
+    /// contain exactly two nodes. A minimum hitting set of such an instance is
+    /// a minimum vertex cover of the graph.
+    pub fn is_graph(&self) -> bool {
+        self.edges.iter().all(|&edge| self.edge_size(edge) == 2)
+    }
+
+    /// Greedily selects pairwise disjoint edges, preferring edges whose nodes
+    /// have small degrees.
+    ///
+    /// The selection is maximal, i.e., every other edge intersects one of the
+    /// selected edges. It is not necessarily of maximum size, though.
+    ///
+    /// Ties are broken by the internal order of the edges, which depends on
+    /// previous deletions and restorations, unless `lowest_index_tie_break`
+    /// is set, in which case edges with lower index are preferred.
+    pub fn maximal_disjoint_edges(&self, lowest_index_tie_break: bool) -> Vec<EdgeIdx> {
+        let mut edges = self.edges.to_vec();
+        if lowest_index_tie_break {
+            edges.sort_unstable();
+        }
+        // Stable sort, so ties keep the previous order
+        edges.sort_by_cached_key(|&edge| {
+            self.edge(edge).fold((0, 0), |(sum, max), node| {
+                let degree = self.node_degree(node);
+                (sum + degree, max.max(degree))
+            })
+        });
+
+        let mut disjoint = vec![true; self.num_edges_total()];
+        edges.retain(|&edge| {
+            if !disjoint[edge.idx()] {
+                return false;
+            }
+
+            for node in self.edge(edge) {
+                for overlapping_edge in self.node(node) {
+                    disjoint[overlapping_edge.idx()] = false;
+                }
+            }
+            true
+        });
+        edges
+    }
+
+    /// Number of unordered pairs of alive edges without a common node, which
+    /// indicates how well the edges can be packed.
+    ///
+    /// Visits the edges sharing a node with each edge, which takes time
+    /// proportional to the sum of the squared node degrees.
+    pub fn disjoint_edge_pairs(&self) -> usize {
+        let mut last_seen_from = vec![None; self.num_edges_total()];
+        let mut num_intersecting = 0;
+        for &edge in self.edges() {
+            for node in self.edge(edge) {
+                for other_edge in self.node(node) {
+                    if other_edge != edge && last_seen_from[other_edge.idx()] != Some(edge) {
+                        last_seen_from[other_edge.idx()] = Some(edge);
+                        num_intersecting += 1;
+                    }
+                }
+            }
+        }
+
+        // Each intersecting pair was counted once from both of its edges
+        let num_edges = self.num_edges();
+        num_edges * num_edges.saturating_sub(1) / 2 - num_intersecting / 2
+    }
+
+    /// Heuristic upper bound on the treewidth of the primal graph, in which
+    /// two nodes are adjacent if they share an edge.
+    ///
+    /// This eliminates nodes in min-degree order, so the result is only an
+    /// estimate. It can take quadratic time and memory on dense instances.
+    pub fn treewidth_upper_bound(&self) -> usize {
+        let mut neighbors = vec![IdxHashSet::default(); self.num_nodes_total()];
+        for &edge in self.edges() {
+            for node1 in self.edge(edge) {
+                for node2 in self.edge(edge).filter(|&node2| node2 != node1) {
+                    neighbors[node1.idx()].insert(node2);
+                }
+            }
+        }
+
+        // Entries become stale as degrees change and are skipped when popped
+        let mut queue: BinaryHeap<_> = self
+            .nodes()
+            .iter()
+            .map(|&node| Reverse((neighbors[node.idx()].len(), node)))
+            .collect();
+        let mut eliminated = vec![false; self.num_nodes_total()];
+        let mut width = 0;
+        while let Some(Reverse((degree, node))) = queue.pop() {
+            if eliminated[node.idx()] || degree != neighbors[node.idx()].len() {
+                continue;
+            }
+            eliminated[node.idx()] = true;
+            width = width.max(degree);
+
+            let node_neighbors: Vec<_> =
+                mem::take(&mut neighbors[node.idx()]).into_iter().collect();
+            for &neighbor in &node_neighbors {
+                neighbors[neighbor.idx()].remove(&node);
+            }
+            for (idx, &neighbor1) in node_neighbors.iter().enumerate() {
+                for &neighbor2 in &node_neighbors[idx + 1..] {
+                    neighbors[neighbor1.idx()].insert(neighbor2);
+                    neighbors[neighbor2.idx()].insert(neighbor1);
+                }
+            }
+            for &neighbor in &node_neighbors {
+                queue.push(Reverse((neighbors[neighbor.idx()].len(), neighbor)));
+            }
+        }
+
+        width
+    }
+
+    /// Describes the differences between the alive parts of two instances,
+    /// for debugging. Returns an empty list if they are identical.
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.num_nodes_total() != other.num_nodes_total() {
+            differences.push(format!(
+                "total node count differs: {} vs. {}",
+                self.num_nodes_total(),
+                other.num_nodes_total()
+            ));
+        }
+        if self.num_edges_total() != other.num_edges_total() {
+            differences.push(format!(
+                "total edge count differs: {} vs. {}",
+                self.num_edges_total(),
+                other.num_edges_total()
+            ));
+        }
+
+        let other_nodes: IdxHashSet<_> = other.nodes().iter().copied().collect();
+        for node in self.nodes_sorted() {
+            if !other_nodes.contains(&node) {
+                differences.push(format!("node {node} is only alive in the first instance"));
+            } else if !self.node(node).eq(other.node(node)) {
+                differences.push(format!("incidences of node {node} differ"));
+            }
+        }
+        let self_nodes: IdxHashSet<_> = self.nodes().iter().copied().collect();
+        for node in other.nodes_sorted() {
+            if !self_nodes.contains(&node) {
+                differences.push(format!("node {node} is only alive in the second instance"));
+            }
+        }
+
+        let other_edges: IdxHashSet<_> = other.edges().iter().copied().collect();
+        for edge in self.edges_sorted() {
+            if !other_edges.contains(&edge) {
+                differences.push(format!("edge {edge} is only alive in the first instance"));
+            } else if !self.edge(edge).eq(other.edge(edge)) {
+                differences.push(format!("nodes of edge {edge} differ"));
+            }
+        }
+        let self_edges: IdxHashSet<_> = self.edges().iter().copied().collect();
+        for edge in other.edges_sorted() {
+            if !self_edges.contains(&edge) {
+                differences.push(format!("edge {edge} is only alive in the second instance"));
+            }
+        }
+
+        differences
+    }
+
+    /// Recounts the degrees of all alive nodes and edges and checks them
+    /// against the maintained ones, for debugging the bookkeeping of
+    /// deletions and restorations.
+    pub fn check_degrees(&self) -> Result<()> {
+        let mut node_degree_counts = vec![0; self.node_degree_counts.len()];
+        for &node in self.nodes() {
+            let degree = self.node(node).count();
+            ensure!(
+                degree == self.node_degree(node),
+                "node {} has degree {}, but {} is stored",
+                node,
+                degree,
+                self.node_degree(node)
+            );
+            ensure!(
+                self.node(node)
+                    .all(|edge| !self.edges.is_deleted(edge.idx())),
+                "node {} is incident to a deleted edge",
+                node
+            );
+            node_degree_counts[degree] += 1;
+        }
+        ensure!(
+            node_degree_counts == self.node_degree_counts,
+            "node degree histogram differs from the stored one"
+        );
+        let max_node_degree = self
+            .nodes()
+            .iter()
+            .map(|&node| self.node_degree(node))
+            .max();
+        ensure!(
+            self.max_node_degree() == max_node_degree,
+            "maximum node degree is {:?}, but {:?} is stored",
+            max_node_degree,
+            self.max_node_degree()
+        );
+
+        for &edge in self.edges() {
+            let size = self.edge(edge).count();
+            ensure!(
+                size == self.edge_size(edge),
+                "edge {} has size {}, but {} is stored",
+                edge,
+                size,
+                self.edge_size(edge)
+            );
+            ensure!(
+                self.edge(edge)
+                    .all(|node| !self.nodes.is_deleted(node.idx())),
+                "edge {} contains a deleted node",
+                edge
+            );
+        }
+        Ok(())
+    }
+
     /// Deletes a node from the instance.
     pub fn delete_node(&mut self, node: NodeIdx) {
         trace!("Deleting node {}", node);
         for (_idx, (edge, entry_idx)) in &self.node_incidences[node.idx()] {
+            debug_assert_eq!(
+                self.edge_incidences[edge.idx()][entry_idx.idx()].0,
+                node,
+                "Corrupted back-reference from node {node} into edge {edge}"
+            );
             self.edge_incidences[edge.idx()].delete(entry_idx.idx());
         }
         self.nodes.delete(node.idx());
+        self.node_degree_counts[self.node_incidences[node.idx()].len()] -= 1;
+        self.decrease_max_node_degree();
     }
 
     /// Deletes an edge from the instance.
     pub fn delete_edge(&mut self, edge: EdgeIdx) {
         trace!("Deleting edge {}", edge);
         for (_idx, (node, entry_idx)) in &self.edge_incidences[edge.idx()] {
+            debug_assert_eq!(
+                self.node_incidences[node.idx()][entry_idx.idx()].0,
+                edge,
+                "Corrupted back-reference from edge {edge} into node {node}"
+            );
+            let degree = self.node_incidences[node.idx()].len();
             self.node_incidences[node.idx()].delete(entry_idx.idx());
+            self.node_degree_counts[degree] -= 1;
+            self.node_degree_counts[degree - 1] += 1;
         }
         self.edges.delete(edge.idx());
+        self.decrease_max_node_degree();
     }
 
     /// Restores a previously deleted node.
@@ -259,6 +1300,9 @@ impl Instance {
             self.edge_incidences[edge.idx()].restore(entry_idx.idx());
         }
         self.nodes.restore(node.idx());
+        let degree = self.node_incidences[node.idx()].len();
+        self.node_degree_counts[degree] += 1;
+        self.max_node_degree = self.max_node_degree.max(degree);
     }
 
     /// Restores a previously deleted edge.
@@ -267,8 +1311,16 @@ impl Instance {
     /// the corresponding deletions to produce sensible results.
     pub fn restore_edge(&mut self, edge: EdgeIdx) {
         trace!("Restoring edge {}", edge);
+        debug_assert!(
+            self.edges.is_deleted(edge.idx()),
+            "Restoring edge {edge} which is not deleted"
+        );
         for (_idx, (node, entry_idx)) in self.edge_incidences[edge.idx()].iter().rev() {
+            let degree = self.node_incidences[node.idx()].len();
             self.node_incidences[node.idx()].restore(entry_idx.idx());
+            self.node_degree_counts[degree] -= 1;
+            self.node_degree_counts[degree + 1] += 1;
+            self.max_node_degree = self.max_node_degree.max(degree + 1);
         }
         self.edges.restore(edge.idx());
     }
@@ -313,21 +1365,32 @@ impl Instance {
 
         // It is important that we restore the edges in reverse order
         for (_, (edge, _)) in incidence.iter().rev() {
+            debug_assert!(
+                self.edges.is_deleted(edge.idx()),
+                "Edge {edge} incident to {node} was not deleted by delete_incident_edges"
+            );
             self.restore_edge(*edge);
         }
         self.node_incidences[node.idx()] = incidence;
     }
 
     pub fn export_as_ilp(&self, mut writer: impl Write) -> Result<()> {
+        let nodes = self.nodes_sorted();
         writeln!(writer, "Minimize")?;
-        write!(writer, "  v{}", CompressedIlpName(self.nodes()[0]))?;
-        for &node in &self.nodes()[1..] {
-            write!(writer, " + v{}", CompressedIlpName(node))?;
+        if let Some((&first_node, other_nodes)) = nodes.split_first() {
+            write!(writer, "  v{}", CompressedIlpName(first_node))?;
+            for &node in other_nodes {
+                write!(writer, " + v{}", CompressedIlpName(node))?;
+            }
+        } else {
+            // Without any nodes (e.g. after reducing), only a named empty
+            // objective is valid
+            write!(writer, "  obj:")?;
         }
         writeln!(writer)?;
 
         writeln!(writer, "Subject To")?;
-        for &edge in self.edges() {
+        for edge in self.edges_sorted() {
             write!(writer, "  e{}: ", CompressedIlpName(edge))?;
             for (idx, node) in self.edge(edge).enumerate() {
                 if idx > 0 {
@@ -338,24 +1401,466 @@ impl Instance {
             writeln!(writer, " >= 1")?;
         }
 
-        writeln!(writer, "Binaries")?;
-        write!(writer, "  v{}", CompressedIlpName(self.nodes()[0]))?;
-        for &node in &self.nodes()[1..] {
-            write!(writer, " v{}", CompressedIlpName(node))?;
+        if let Some((&first_node, other_nodes)) = nodes.split_first() {
+            writeln!(writer, "Binaries")?;
+            write!(writer, "  v{}", CompressedIlpName(first_node))?;
+            for &node in other_nodes {
+                write!(writer, " v{}", CompressedIlpName(node))?;
+            }
+            writeln!(writer)?;
         }
-        writeln!(writer)?;
 
         writeln!(writer, "End")?;
         Ok(())
     }
 
-    pub fn export_as_text(&self, mut writer: impl Write) -> Result<()> {
-        for &edge in self.edges() {
+    /// Writes the hitting set ILP in the pseudo-Boolean OPB format used by
+    /// PB solvers. Node `i` becomes the variable `x{i+1}`, since OPB variable
+    /// numbers start at one.
+    pub fn export_as_opb(&self, mut writer: impl Write) -> Result<()> {
+        writeln!(
+            writer,
+            "* #variable= {} #constraint= {}",
+            self.num_nodes_total(),
+            self.num_edges()
+        )?;
+        write!(writer, "min:")?;
+        for node in self.nodes_sorted() {
+            write!(writer, " +1 x{}", node.idx() + 1)?;
+        }
+        writeln!(writer, " ;")?;
+
+        for edge in self.edges_sorted() {
+            for node in self.edge(edge) {
+                write!(writer, "+1 x{} ", node.idx() + 1)?;
+            }
+            writeln!(writer, ">= 1 ;")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the instance in the text format with a header line and a size
+    /// prefix on each edge line. Edges are ordered by their sorted node
+    /// lists, so instances with the same edges produce the same output.
+    pub fn export_as_canonical_text(
+        &self,
+        mut writer: impl Write,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        let offset = usize::from(options.one_indexed);
+        let mut edges: Vec<Vec<_>> = self
+            .edges()
+            .iter()
+            .map(|&edge| self.edge(edge).collect())
+            .collect();
+        edges.sort_unstable();
+        writeln!(writer, "{} {}", self.num_nodes_total(), edges.len())?;
+        for edge in &edges {
+            write!(writer, "{}", edge.len())?;
+            for node in edge {
+                write!(writer, " {}", node.idx() + offset)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Hash of the alive edges that depends neither on the order of the
+    /// edges nor on the order of the nodes within them, so an instance
+    /// written by `export_as_text` and read back keeps its fingerprint.
+    pub fn fingerprint(&self) -> u64 {
+        let mut edges: Vec<Vec<NodeIdx>> = self
+            .edges()
+            .iter()
+            .map(|&edge| {
+                let mut nodes: Vec<_> = self.edge(edge).collect();
+                nodes.sort_unstable();
+                nodes
+            })
+            .collect();
+        edges.sort_unstable();
+        let mut hasher = FxHasher::default();
+        edges.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn export_as_text(&self, mut writer: impl Write, options: &ExportOptions) -> Result<()> {
+        let offset = usize::from(options.one_indexed);
+        for edge in self.edges_sorted() {
             for node in self.edge(edge) {
-                write!(writer, " {}", node.idx())?;
+                write!(writer, " {}", node.idx() + offset)?;
             }
             writeln!(writer)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &str) -> Result<Instance> {
+        Instance::load_from_text(text.as_bytes(), &LoadOptions::default())
+    }
+
+    #[test]
+    fn edges_sorted_by_size_then_index() {
+        let mut instance = load("4 4\n3 0 1 2\n2 2 3\n2 0 1\n3 1 2 3\n").unwrap();
+        let edges = |instance: &Instance| -> Vec<_> {
+            instance
+                .edges_sorted_by_size()
+                .iter()
+                .map(EdgeIdx::idx)
+                .collect()
+        };
+        assert_eq!(edges(&instance), [1, 2, 0, 3]);
+
+        // Deleting node 2 shrinks edges 0, 1 and 3
+        instance.delete_node(NodeIdx::from(2_usize));
+        assert_eq!(edges(&instance), [1, 0, 2, 3]);
+        instance.delete_edge(EdgeIdx::from(1_usize));
+        assert_eq!(edges(&instance), [0, 2, 3]);
+    }
+
+    #[test]
+    fn trivial_hitting_set_hits_all_edges() {
+        let mut instance = load("6 5\n2 0 1\n2 1 2\n3 2 3 4\n2 4 5\n2 0 5\n").unwrap();
+        let hs = instance.trivial_hitting_set();
+        assert!(instance.uncovered_edges(&hs).is_empty());
+        assert!(hs.len() <= instance.num_edges());
+
+        instance.delete_edge(EdgeIdx::from(0_usize));
+        instance.delete_edge(EdgeIdx::from(1_usize));
+        let hs = instance.trivial_hitting_set();
+        assert!(instance.uncovered_edges(&hs).is_empty());
+        assert!(hs.len() <= 3);
+    }
+
+    #[test]
+    fn checked_accessors_reject_out_of_range_indices() {
+        let instance = load("2 1\n2 0 1\n").unwrap();
+        assert_eq!(instance.try_edge(EdgeIdx::from(0_usize)).unwrap().len(), 2);
+        assert_eq!(instance.try_node(NodeIdx::from(1_usize)).unwrap().len(), 1);
+        assert!(instance.try_edge(EdgeIdx::from(1_usize)).is_err());
+        assert!(instance.try_node(NodeIdx::from(2_usize)).is_err());
+    }
+
+    #[test]
+    fn removes_repeated_node_in_edge() {
+        let instance = load("2 2\n2 0 1\n3 1 0 1\n").unwrap();
+        assert_eq!(instance.num_edges(), 2);
+        let edge: Vec<_> = instance.edge(EdgeIdx::from(1_usize)).collect();
+        assert_eq!(edge, [NodeIdx::from(0_usize), NodeIdx::from(1_usize)]);
+        assert_eq!(instance.node_degree(NodeIdx::from(0_usize)), 2);
+    }
+
+    #[test]
+    fn input_edge_indices_survive_skipped_edges() {
+        let options = LoadOptions {
+            skip_duplicate_edges: true,
+            max_edge_degree: Some(2),
+            drop_large_edges: true,
+            ..LoadOptions::default()
+        };
+        let text = "4 5\n2 0 1\n3 0 1 2\n2 1 0\n2 2 3\n1 3\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &options).unwrap();
+        assert_eq!(instance.num_edges(), 3);
+        let kept: Vec<_> = [0_usize, 3, 4]
+            .into_iter()
+            .map(|idx| instance.edge_from_input_index(idx).unwrap().unwrap())
+            .collect();
+        assert_eq!(
+            kept.iter()
+                .map(|&edge| instance.input_edge_index(edge))
+                .collect::<Vec<_>>(),
+            [0, 3, 4]
+        );
+        assert_eq!(
+            instance.edge(kept[1]).collect::<Vec<_>>(),
+            [2_usize, 3].map(NodeIdx::from)
+        );
+        assert!(instance.edge_from_input_index(1).unwrap().is_none());
+        assert!(instance.edge_from_input_index(2).unwrap().is_none());
+        assert!(instance.edge_from_input_index(5).is_err());
+    }
+
+    #[test]
+    fn nodes_only_in_large_edges() {
+        // Nodes 1 and 4 are in the small edge, node 2 is in no edge at all
+        let instance = load("5 2\n3 0 1 3\n2 1 4\n").unwrap();
+        let nodes: Vec<_> = [0_usize, 2, 3].into_iter().map(NodeIdx::from).collect();
+        assert_eq!(instance.nodes_only_in_large_edges(2), nodes);
+        assert_eq!(
+            instance.nodes_only_in_large_edges(3),
+            vec![NodeIdx::from(2_usize)]
+        );
+    }
+
+    #[test]
+    fn opb_constraints_match_alive_edges() {
+        let opb = |instance: &Instance| {
+            let mut output = Vec::new();
+            instance.export_as_opb(&mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let mut instance = load("3 2\n2 0 1\n2 1 2\n").unwrap();
+        assert_eq!(
+            opb(&instance),
+            "* #variable= 3 #constraint= 2\nmin: +1 x1 +1 x2 +1 x3 ;\n\
+             +1 x1 +1 x2 >= 1 ;\n+1 x2 +1 x3 >= 1 ;\n"
+        );
+
+        instance.delete_edge(EdgeIdx::from(0_usize));
+        instance.delete_node(NodeIdx::from(0_usize));
+        assert_eq!(
+            opb(&instance),
+            "* #variable= 3 #constraint= 1\nmin: +1 x2 +1 x3 ;\n+1 x2 +1 x3 >= 1 ;\n"
+        );
+    }
+
+    #[test]
+    fn rejects_more_nodes_than_indices_can_address() {
+        let error = load("4294967296 1\n1 0\n").unwrap_err();
+        assert!(format!("{error:#}").contains("supported maximum"));
+
+        // The sentinel as a node index needs one more node than supported
+        let options = LoadOptions {
+            infer_node_count: true,
+            ..LoadOptions::default()
+        };
+        let error = Instance::load_from_text(&b"2 0 4294967295\n"[..], &options).unwrap_err();
+        assert!(format!("{error:#}").contains("supported maximum"));
+    }
+
+    #[test]
+    fn node_labels_from_comment_section() {
+        let options = LoadOptions {
+            comments: true,
+            ..LoadOptions::default()
+        };
+        let text = "# source: test\n# nodes:\n# left\n# middle\n# right\n3 2\n2 0 1\n2 1 2\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &options).unwrap();
+        assert_eq!(instance.metadata()["source"], "test");
+        let labels = instance.node_labels().unwrap();
+        assert_eq!(labels, ["left", "middle", "right"]);
+        // The middle node alone hits both edges
+        let hs = [NodeIdx::from(1_usize)];
+        assert!(crate::solve::is_hitting_set(&hs, &instance));
+        let hs_labels: Vec<_> = hs.iter().map(|node| &labels[node.idx()]).collect();
+        assert_eq!(hs_labels, ["middle"]);
+
+        let text = "# nodes:\n# left\n# right\n3 2\n2 0 1\n2 1 2\n";
+        let error = Instance::load_from_text(text.as_bytes(), &options).unwrap_err();
+        assert!(format!("{error:#}").contains("2 node labels for 3 nodes"));
+    }
+
+    #[test]
+    fn dropped_edges_release_capacity() {
+        let options = LoadOptions {
+            max_edge_degree: Some(2),
+            drop_large_edges: true,
+            ..LoadOptions::default()
+        };
+        let text = "4 4\n3 0 1 2\n2 0 1\n4 0 1 2 3\n2 2 3\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &options).unwrap();
+        assert_eq!(instance.num_edges(), 2);
+        assert_eq!(instance.edge_incidences.capacity(), 2);
+        let edges: Vec<Vec<_>> = instance
+            .edges()
+            .iter()
+            .map(|&edge| instance.edge(edge).map(|node| node.idx()).collect())
+            .collect();
+        assert_eq!(edges, [[0, 1], [2, 3]]);
+    }
+
+    #[test]
+    fn parse_number_agrees_with_str_parse() {
+        let overflowing = format!("{}0", usize::MAX);
+        let max = usize::MAX.to_string();
+        for token in [
+            "0",
+            "7",
+            "+7",
+            "0042",
+            &max,
+            &overflowing,
+            "",
+            "+",
+            "-1",
+            "1a",
+            "a1",
+            " 1",
+            "1.0",
+            "++1",
+        ] {
+            assert_eq!(
+                parse_number(token).ok(),
+                token.parse::<usize>().ok(),
+                "token '{token}'"
+            );
+        }
+    }
+
+    #[test]
+    fn degrees_stay_consistent_under_deletions() {
+        enum Deletion {
+            Node(NodeIdx),
+            NodeWithEdges(NodeIdx),
+            Edge(EdgeIdx),
+        }
+
+        let mut instance =
+            load("6 7\n3 0 1 2\n2 1 3\n3 2 3 4\n2 4 5\n4 0 2 4 5\n2 1 5\n1 3\n").unwrap();
+        let mut seed = 0x2545_f491_u32;
+        let mut random = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % bound
+        };
+        let mut deletions = Vec::new();
+        for _ in 0..500 {
+            let choice = random(4);
+            if choice == 0 || instance.nodes().is_empty() || instance.num_edges() == 0 {
+                match deletions.pop() {
+                    Some(Deletion::Node(node)) => instance.restore_node(node),
+                    Some(Deletion::NodeWithEdges(node)) => {
+                        instance.restore_incident_edges(node);
+                        instance.restore_node(node);
+                    }
+                    Some(Deletion::Edge(edge)) => instance.restore_edge(edge),
+                    None => {}
+                }
+            } else if choice == 1 {
+                let edge = instance.edges()[random(instance.num_edges())];
+                instance.delete_edge(edge);
+                deletions.push(Deletion::Edge(edge));
+            } else {
+                let node = instance.nodes()[random(instance.nodes().len())];
+                instance.delete_node(node);
+                if choice == 2 {
+                    deletions.push(Deletion::Node(node));
+                } else {
+                    instance.delete_incident_edges(node);
+                    deletions.push(Deletion::NodeWithEdges(node));
+                }
+            }
+            instance.check_degrees().unwrap();
+        }
+
+        let mut corrupted = instance.clone();
+        corrupted.node_degree_counts[0] += 1;
+        assert!(corrupted.check_degrees().is_err());
+    }
+
+    #[test]
+    fn merged_parts_equal_concatenation() {
+        let parts = [
+            load("4 2\n2 0 1\n2 1 2\n").unwrap(),
+            load("5 2\n2 3 4\n3 0 2 4\n").unwrap(),
+        ];
+        let merged = Instance::merge(&parts).unwrap();
+        let concatenated = load("5 4\n2 0 1\n2 1 2\n2 3 4\n3 0 2 4\n").unwrap();
+        let edges = |instance: &Instance| -> Vec<Vec<_>> {
+            instance
+                .edges()
+                .iter()
+                .map(|&edge| instance.edge(edge).map(|node| node.idx()).collect())
+                .collect()
+        };
+        assert_eq!(merged.num_nodes_total(), concatenated.num_nodes_total());
+        assert_eq!(edges(&merged), edges(&concatenated));
+        for &node in concatenated.nodes() {
+            assert_eq!(merged.node_degree(node), concatenated.node_degree(node));
+        }
+    }
+
+    #[test]
+    fn checked_accessors_report_the_bad_index() {
+        let instance = load("2 1\n2 0 1\n").unwrap();
+        let error = instance.try_edge(EdgeIdx::INVALID).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!("edge index {} out of bounds for 1 edges", EdgeIdx::INVALID)
+        );
+        let error = instance.try_node(NodeIdx::from(5_usize)).err().unwrap();
+        assert_eq!(error.to_string(), "node index 5 out of bounds for 2 nodes");
+    }
+
+    #[test]
+    fn disjoint_edge_pairs_of_a_cycle() {
+        // In the cycle of length four, opposite edges are disjoint
+        let mut instance = load("4 4\n2 0 1\n2 1 2\n2 2 3\n2 0 3\n").unwrap();
+        assert_eq!(instance.disjoint_edge_pairs(), 2);
+        instance.delete_edge(EdgeIdx::from(1_usize));
+        assert_eq!(instance.disjoint_edge_pairs(), 1);
+
+        let triangle = load("3 3\n2 0 1\n2 1 2\n2 0 2\n").unwrap();
+        assert_eq!(triangle.disjoint_edge_pairs(), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Edge 1 incident to 1 was not deleted by delete_incident_edges")]
+    fn restoring_alive_incident_edges_is_caught() {
+        let mut instance = load("3 2\n2 0 1\n2 1 2\n").unwrap();
+        let node = NodeIdx::from(1_usize);
+        instance.delete_node(node);
+        instance.restore_incident_edges(node);
+    }
+
+    #[test]
+    fn canonical_text_reloads_to_the_same_edges() {
+        let options = LoadOptions {
+            comments: true,
+            ..LoadOptions::default()
+        };
+        let messy = "# messy input\n4 3\n3 3 0 2\n# between edges\n2 2 1\n2 1 0\n";
+        let instance = Instance::load_from_text(messy.as_bytes(), &options).unwrap();
+        let mut clean = Vec::new();
+        instance
+            .export_as_canonical_text(&mut clean, &ExportOptions::default())
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(clean.clone()).unwrap(),
+            "4 3\n2 0 1\n3 0 2 3\n2 1 2\n"
+        );
+
+        let reloaded = load(std::str::from_utf8(&clean).unwrap()).unwrap();
+        let sorted_edges = |instance: &Instance| {
+            let mut edges: Vec<Vec<_>> = instance
+                .edges()
+                .iter()
+                .map(|&edge| instance.edge(edge).collect())
+                .collect();
+            edges.sort_unstable();
+            edges
+        };
+        assert_eq!(reloaded.num_nodes_total(), instance.num_nodes_total());
+        assert_eq!(sorted_edges(&reloaded), sorted_edges(&instance));
+
+        let mut clean_again = Vec::new();
+        reloaded
+            .export_as_canonical_text(&mut clean_again, &ExportOptions::default())
+            .unwrap();
+        assert_eq!(clean_again, clean);
+    }
+
+    #[test]
+    fn header_without_nodes() {
+        let empty = load("0 0\n").unwrap();
+        assert_eq!(empty.num_nodes_total(), 0);
+        assert_eq!(empty.num_edges(), 0);
+        let settings = serde_json::from_str(include_str!("../settings.json")).unwrap();
+        let (hs, report) = crate::solve::solve(empty, String::new(), settings).unwrap();
+        assert!(hs.is_empty());
+        assert_eq!(report.opt, 0);
+
+        let error = load("0 2\n1 0\n1 0\n").unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "instance has 2 edges but no nodes, so every edge would be empty"
+        );
+    }
+}
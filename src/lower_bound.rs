@@ -5,16 +5,14 @@ use crate::{
     report::Settings,
     small_indices::{IdxHashSet, SmallIdx},
 };
+use log::warn;
 use std::iter::Peekable;
 
 create_idx_struct!(PackingIdx);
 
 pub fn calc_max_degree_bound(instance: &Instance) -> Option<usize> {
     instance
-        .nodes()
-        .iter()
-        .map(|&node| instance.node_degree(node))
-        .max()
+        .max_node_degree()
         .map(|max_degree| (instance.num_edges() + max_degree - 1) / max_degree)
 }
 
@@ -98,6 +96,83 @@ pub fn calc_efficiency_bound(instance: &Instance) -> (EfficiencyBound, Vec<Effic
     (bound, discard_bounds)
 }
 
+/// Lower bound from a fractional edge packing, i.e., the value of a feasible
+/// solution to the dual of the hitting set LP relaxation.
+///
+/// Starts from the weights used by the efficiency bound and greedily raises
+/// each edge weight until one of its nodes is saturated. The result is thus
+/// never worse than the efficiency bound and captures structures like odd
+/// cycles where every integral packing is weak.
+pub fn calc_fractional_packing_bound(instance: &Instance) -> EfficiencyBound {
+    calc_fractional_packing(instance).0
+}
+
+/// Computes the fractional edge packing of `calc_fractional_packing_bound`.
+/// Returns its value together with the total weight of the edges incident to
+/// each node, which is at most 1.
+#[allow(clippy::cast_precision_loss)]
+pub fn calc_fractional_packing(instance: &Instance) -> (EfficiencyBound, Vec<f64>) {
+    let mut load = vec![0.0; instance.num_nodes_total()];
+    let mut weights = Vec::with_capacity(instance.num_edges());
+    for &edge in instance.edges() {
+        let max_degree = instance
+            .edge(edge)
+            .map(|node| instance.node_degree(node))
+            .max()
+            .unwrap_or(0);
+        let weight = (max_degree as f64).recip();
+        for node in instance.edge(edge) {
+            load[node.idx()] += weight;
+        }
+        weights.push(weight);
+    }
+
+    let mut edges: Vec<_> = instance.edges().iter().copied().zip(weights).collect();
+    edges.sort_unstable_by_key(|&(edge, _)| instance.edge_size(edge));
+    let mut bound = EfficiencyBound(0.0);
+    for (edge, mut weight) in edges {
+        let slack = instance
+            .edge(edge)
+            .map(|node| 1.0 - load[node.idx()])
+            .fold(f64::INFINITY, f64::min);
+        if slack.is_finite() && slack > 0.0 {
+            weight += slack;
+            for node in instance.edge(edge) {
+                load[node.idx()] += slack;
+            }
+        }
+        bound.0 += weight;
+    }
+
+    (bound, load)
+}
+
+/// Ranks the nodes by how likely they are to be part of a small hitting set,
+/// most likely first.
+///
+/// The score of a node is its load in the fractional edge packing, which is
+/// 1 for the nodes picked by the primal-dual approximation. Ties are broken
+/// by larger degree.
+pub fn rank_nodes(instance: &Instance) -> Vec<(NodeIdx, f64)> {
+    let (_, load) = calc_fractional_packing(instance);
+    let mut ranking: Vec<_> = instance
+        .nodes()
+        .iter()
+        .map(|&node| (node, load[node.idx()]))
+        .collect();
+    ranking.sort_unstable_by(|&(node1, score1), &(node2, score2)| {
+        score2
+            .total_cmp(&score1)
+            .then_with(|| {
+                instance
+                    .node_degree(node2)
+                    .cmp(&instance.node_degree(node1))
+            })
+            .then_with(|| node1.cmp(&node2))
+    });
+    ranking
+}
+
 #[derive(Debug, Default)]
 pub struct PackingBound {
     packing: Vec<EdgeIdx>,
@@ -105,30 +180,11 @@ pub struct PackingBound {
 
 impl PackingBound {
     pub fn new(instance: &Instance, settings: &Settings) -> Self {
-        let mut packing: Vec<_> = instance.edges().to_vec();
-        packing.sort_by_cached_key(|&edge| {
-            instance.edge(edge).fold((0, 0), |(sum, max), node| {
-                let degree = instance.node_degree(node);
-                (sum + degree, max.max(degree))
-            })
-        });
-
-        let mut disjoint = vec![true; instance.num_edges_total()];
-        packing.retain(|&edge| {
-            if !disjoint[edge.idx()] {
-                return false;
-            }
-
-            for node in instance.edge(edge) {
-                for overlapping_edge in instance.node(node) {
-                    disjoint[overlapping_edge.idx()] = false;
-                }
-            }
-            true
-        });
-
+        let mut packing = instance.maximal_disjoint_edges(settings.lowest_index_tie_break);
         if settings.enable_local_search {
-            packing = improve_packing_by_local_search(instance, packing);
+            // Every swap grows the packing, so there can be at most one swap
+            // per edge. Exceeding this means that swaps undo each other.
+            packing = improve_packing_by_local_search(instance, packing, instance.num_edges());
         }
 
         Self { packing }
@@ -138,8 +194,23 @@ impl PackingBound {
         self.packing.len()
     }
 
+    pub fn packing(&self) -> &[EdgeIdx] {
+        &self.packing
+    }
+
     pub fn calc_sum_over_packing_bound(&self, instance: &Instance) -> usize {
-        let mut adjusted_degrees = vec![0; instance.num_nodes_total()];
+        self.calc_sum_over_packing_bound_in(&mut Vec::new(), instance)
+    }
+
+    /// Same as `calc_sum_over_packing_bound`, but reuses the given buffer
+    /// instead of allocating a new one on every call.
+    pub fn calc_sum_over_packing_bound_in(
+        &self,
+        adjusted_degrees: &mut Vec<usize>,
+        instance: &Instance,
+    ) -> usize {
+        adjusted_degrees.clear();
+        adjusted_degrees.resize(instance.num_nodes_total(), 0);
         let mut covered_edges = 0;
         for &node in instance.nodes() {
             adjusted_degrees[node.idx()] = instance.node_degree(node);
@@ -161,9 +232,9 @@ impl PackingBound {
 
         adjusted_degrees.sort_unstable();
         let sum_bound = adjusted_degrees
-            .into_iter()
+            .iter()
             .rev()
-            .take_while(|&degree| {
+            .take_while(|&&degree| {
                 if covered_edges < instance.num_edges() {
                     covered_edges += degree;
                     true
@@ -344,7 +415,11 @@ fn find_two_opt_swap(
     None
 }
 
-fn improve_packing_by_local_search(instance: &Instance, mut packing: Vec<EdgeIdx>) -> Vec<EdgeIdx> {
+fn improve_packing_by_local_search(
+    instance: &Instance,
+    mut packing: Vec<EdgeIdx>,
+    max_swaps: usize,
+) -> Vec<EdgeIdx> {
     let packing_set: IdxHashSet<_> = packing.iter().copied().collect();
     let mut remaining: Vec<_> = instance
         .edges()
@@ -358,6 +433,8 @@ fn improve_packing_by_local_search(instance: &Instance, mut packing: Vec<EdgeIdx
     let mut blocked_by: Vec<Vec<_>> = Vec::new();
     let mut available_nodes = Vec::new();
 
+    let mut num_swaps = 0;
+
     loop {
         // For each node, calculate which packing edge is hitting it (if any)
         hit_by.fill(PackingIdx::INVALID);
@@ -406,6 +483,14 @@ fn improve_packing_by_local_search(instance: &Instance, mut packing: Vec<EdgeIdx
             Some(tuple) => tuple,
             None => return packing,
         };
+        if num_swaps >= max_swaps {
+            warn!(
+                "Aborting packing local search after {} swaps, swaps seem to cycle",
+                num_swaps
+            );
+            return packing;
+        }
+        num_swaps += 1;
 
         let removed_edge = packing[removed_edge_idx.idx()];
         packing.retain(|&edge| edge != removed_edge);
@@ -442,3 +527,47 @@ fn improve_packing_by_local_search(instance: &Instance, mut packing: Vec<EdgeIdx
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::LoadOptions;
+
+    #[test]
+    fn ranked_nodes_with_full_load_hit_all_edges() {
+        // A path and a triangle, both with minimum hitting sets of size 2
+        let text = "7 6\n2 0 1\n2 1 2\n2 2 3\n2 4 5\n2 5 6\n2 4 6\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let ranking = rank_nodes(&instance);
+        assert_eq!(ranking.len(), 7);
+        assert!(ranking.windows(2).all(|window| window[0].1 >= window[1].1));
+
+        // The nodes with load 1 are the primal-dual hitting set, which is at
+        // most twice as large as a minimum one in a graph
+        let top: Vec<_> = ranking
+            .iter()
+            .take_while(|&&(_, score)| score > 1.0 - 1e-9)
+            .map(|&(node, _)| node)
+            .collect();
+        assert!(instance
+            .edges()
+            .iter()
+            .all(|&edge| instance.edge(edge).any(|node| top.contains(&node))));
+        assert!(top.len() <= 2 * 4);
+    }
+
+    #[test]
+    fn local_search_stops_at_swap_limit() {
+        // The middle edge of the path blocks both outer edges
+        let text = "4 3\n2 1 2\n2 0 1\n2 2 3\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let middle = vec![EdgeIdx::from(0_usize)];
+
+        let packing = improve_packing_by_local_search(&instance, middle.clone(), 0);
+        assert_eq!(packing, middle);
+
+        let mut packing = improve_packing_by_local_search(&instance, middle, instance.num_edges());
+        packing.sort_unstable();
+        assert_eq!(packing, [EdgeIdx::from(1_usize), EdgeIdx::from(2_usize)]);
+    }
+}
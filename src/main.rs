@@ -1,25 +1,39 @@
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(clippy::similar_names, clippy::cast_possible_truncation)]
-use crate::{instance::Instance, report::IlpReductionReport};
-use anyhow::{anyhow, Result};
-use log::{debug, info};
+use crate::{
+    instance::{ExportOptions, Instance, LoadOptions, NodeIdx},
+    proof::Proof,
+    reductions::ReductionResult,
+    report::{IlpReductionReport, Report, SearchTreeNode, Settings},
+    small_indices::SmallIdx,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
 use std::{
     ffi::OsStr,
-    fs::File,
-    io::{self, BufReader, BufWriter},
-    path::PathBuf,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
     time::Instant,
 };
 use structopt::{clap::AppSettings, StructOpt};
 
 mod data_structures;
+mod enumerate;
+mod gzip;
 mod instance;
 mod lower_bound;
+mod proof;
 mod reductions;
 mod report;
 mod small_indices;
 mod solve;
 
+/// Path that reads a hypergraph from stdin instead of a file
+const STDIN_PATH: &str = "-";
+
 const APP_SETTINGS: &[AppSettings] = &[
     AppSettings::DisableHelpSubcommand,
     AppSettings::SubcommandRequiredElseHelp,
@@ -30,6 +44,7 @@ const GLOBAL_APP_SETTINGS: &[AppSettings] =
 
 #[derive(Debug, StructOpt)]
 #[structopt(settings = APP_SETTINGS, global_settings = GLOBAL_APP_SETTINGS)]
+#[allow(clippy::large_enum_variant)]
 enum CliOpts {
     /// Run the solver on a given hypergraph
     Solve(SolveOpts),
@@ -39,26 +54,150 @@ enum CliOpts {
 
     /// Only apply reduction rules and then write back to file
     Reduce(ReduceOpts),
+
+    /// Enumerate all inclusion-minimal hitting sets up to a given size
+    Enumerate(EnumerateOpts),
+
+    /// Only calculate the lower and upper bounds used at the root
+    Bounds(BoundsOpts),
+
+    /// Solve a hypergraph repeatedly to measure the runtime of the solver
+    Bench(BenchOpts),
+
+    /// Write a hypergraph in the canonical text-based format
+    Clean(CleanOpts),
+
+    /// Rank the nodes by how likely they are to be part of a small hitting set, without solving
+    Rank(RankOpts),
 }
 
 #[derive(Debug, StructOpt)]
+#[allow(clippy::struct_excessive_bools)]
 struct CommonOpts {
-    /// Input hypergraph
+    /// Input hypergraph, or `-` to read it from stdin
     #[structopt(parse(from_os_str), value_name = "hypergraph-file")]
     hypergraph: PathBuf,
 
+    /// Additional hypergraph files whose edges are added to the input hypergraph. All files share
+    /// the same nodes.
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    merge: Vec<PathBuf>,
+
     /// Use the json format for the input hypergraph rather than the text-based one.
     #[structopt(short, long)]
     json: bool,
+
+    /// Edge lines in the text-based input only list the nodes, without the edge size in front.
+    #[structopt(long)]
+    no_degree_prefix: bool,
+
+    /// Ignore lines after the declared number of edges in the text-based input instead of failing.
+    #[structopt(long)]
+    ignore_trailing_lines: bool,
+
+    /// Read the edges of the text-based input as one stream of numbers, each edge terminated by this value.
+    #[structopt(long, value_name = "value")]
+    edge_separator: Option<usize>,
+
+    /// Node indices in the text-based input and in all written hypergraphs and hitting sets start at 1.
+    #[structopt(long)]
+    one_indexed: bool,
+
+    /// Skip lines starting with '#' in the text-based input, reading `# key: value` lines as metadata.
+    #[structopt(long)]
+    comments: bool,
+
+    /// Ignore numbers after the node and edge count in the first line of the text-based input.
+    #[structopt(long)]
+    ignore_extra_header: bool,
+
+    /// The text-based input has no first line with counts, infer the number of nodes from the edges.
+    #[structopt(long, conflicts_with = "edge-separator")]
+    infer_node_count: bool,
+
+    /// Reject hyperedges with more nodes than this in the text-based input.
+    #[structopt(long, value_name = "degree")]
+    max_edge_degree: Option<usize>,
+
+    /// Drop hyperedges above --max-edge-degree instead of failing. This relaxes the problem!
+    #[structopt(long, requires = "max-edge-degree")]
+    drop_large_edges: bool,
+
+    /// Only keep the first of several hyperedges with the same nodes in the text-based input.
+    #[structopt(long)]
+    skip_duplicate_edges: bool,
 }
 
 impl CommonOpts {
+    fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            no_degree_prefix: self.no_degree_prefix,
+            ignore_trailing_lines: self.ignore_trailing_lines,
+            edge_separator: self.edge_separator,
+            one_indexed: self.one_indexed,
+            comments: self.comments,
+            ignore_extra_header: self.ignore_extra_header,
+            infer_node_count: self.infer_node_count,
+            max_edge_degree: self.max_edge_degree,
+            drop_large_edges: self.drop_large_edges,
+            skip_duplicate_edges: self.skip_duplicate_edges,
+        }
+    }
+
+    fn export_options(&self) -> ExportOptions {
+        ExportOptions {
+            one_indexed: self.one_indexed,
+        }
+    }
+
+    fn output_indices(&self, nodes: &[NodeIdx]) -> Vec<usize> {
+        let offset = usize::from(self.one_indexed);
+        nodes.iter().map(|node| node.idx() + offset).collect()
+    }
+
+    /// Inverse of `output_indices`
+    fn input_indices(&self, raw: &[usize]) -> Result<Vec<NodeIdx>> {
+        let offset = usize::from(self.one_indexed);
+        raw.iter()
+            .map(|&raw_node| {
+                u32::try_from(raw_node)
+                    .ok()
+                    .and_then(|node| NodeIdx::from(node).checked_sub(offset))
+                    .ok_or_else(|| anyhow!("invalid node index: {}", raw_node))
+            })
+            .collect()
+    }
+
     fn load_instance(&self) -> Result<Instance> {
-        let reader = BufReader::new(File::open(&self.hypergraph)?);
+        let instance = self.load_file(&self.hypergraph)?;
+        if self.merge.is_empty() {
+            return Ok(instance);
+        }
+
+        let mut parts = vec![instance];
+        for path in &self.merge {
+            parts.push(self.load_file(path)?);
+        }
+        Instance::merge(&parts)
+    }
+
+    fn load_file(&self, path: &Path) -> Result<Instance> {
+        let reader: Box<dyn BufRead> = if path == Path::new(STDIN_PATH) {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+        self.load_reader(reader)
+            .with_context(|| format!("in file {}", path.display()))
+    }
+
+    fn load_reader(&self, reader: Box<dyn BufRead + '_>) -> Result<Instance> {
+        // Detected by content, since compressed files do not always end in `.gz`
+        let reader = gzip::decompress_if_gzip(reader)?;
         if self.json {
             Instance::load_from_json(reader)
         } else {
-            Instance::load_from_text(reader)
+            Instance::load_from_text(reader, &self.load_options())
         }
     }
 }
@@ -72,6 +211,10 @@ struct IlpOpts {
     #[structopt(long)]
     reduced: bool,
 
+    /// Write the ILP in the pseudo-Boolean OPB format instead of the CPLEX LP format
+    #[structopt(long)]
+    opb: bool,
+
     /// Write a json report about the applied reductions to this file
     #[structopt(
         short,
@@ -84,6 +227,7 @@ struct IlpOpts {
 }
 
 #[derive(Debug, StructOpt)]
+#[allow(clippy::struct_excessive_bools)]
 struct SolveOpts {
     #[structopt(flatten)]
     common: CommonOpts,
@@ -96,9 +240,105 @@ struct SolveOpts {
     #[structopt(short, long, parse(from_os_str), value_name = "file")]
     solution: Option<PathBuf>,
 
+    /// Write the solution using the node labels from a `# nodes:` comment section in the input instead
+    /// of node indices (requires --comments)
+    #[structopt(long, requires("comments"))]
+    labeled: bool,
+
+    /// Write the solution as a json array of booleans with one entry per node, which is true iff
+    /// the node is in the hitting set
+    #[structopt(long, conflicts_with("labeled"))]
+    indicator: bool,
+
     /// Write a detailed statistics report to this file formatted as json
     #[structopt(short, long, parse(from_os_str), value_name = "file")]
     report: Option<PathBuf>,
+
+    /// Append a summary of the run to this file as a csv row, adding a header if the file is empty
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    csv: Option<PathBuf>,
+
+    /// Treat the instance as reduced by `reduce` and add the nodes of the partial hitting set it wrote
+    /// to this file to the final hitting set
+    #[structopt(
+        long,
+        parse(from_os_str),
+        value_name = "file",
+        conflicts_with_all(&["labeled", "indicator"])
+    )]
+    reduced_from: Option<PathBuf>,
+
+    /// Start the search from the hitting set in this file, as written by `--solution`, instead of
+    /// the `initial_hitting_set` from the settings
+    #[structopt(
+        long,
+        parse(from_os_str),
+        value_name = "file",
+        conflicts_with_all(&["labeled", "indicator"])
+    )]
+    initial_solution: Option<PathBuf>,
+
+    /// Solve as if the edge with this index (counting from zero in input order) did not exist, so
+    /// that the hitting set need not hit it. Can be given multiple times.
+    #[structopt(long, value_name = "edge")]
+    ignore_edge: Vec<usize>,
+
+    /// Include this node in the hitting set and search for a minimum completion of it. Can be
+    /// given multiple times.
+    #[structopt(long, value_name = "node")]
+    fix_node: Vec<usize>,
+
+    /// Write the explored branch-and-bound tree to this file as nested json objects
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    search_tree: Option<PathBuf>,
+
+    /// Leave out search tree nodes below this depth when writing the search tree
+    #[structopt(long, value_name = "depth", default_value = "20")]
+    search_tree_depth: usize,
+
+    /// Write the final hitting set together with a disjoint edge packing of the same size, proving it
+    /// is minimum, to this file formatted as json
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    proof: Option<PathBuf>,
+
+    /// Write the proof even if the largest packing found is smaller than the hitting set. The proof
+    /// then certifies the gap between the lower bound and the size of the hitting set.
+    #[structopt(long, requires("proof"))]
+    proof_gap: bool,
+
+    /// Write the nodes contained in all minimum hitting sets ("essential") and those contained in at
+    /// least one ("optional") to this file formatted as json. This enumerates all minimum hitting sets.
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    optimal_nodes: Option<PathBuf>,
+
+    /// Write up to --num-diverse-optima minimum hitting sets that differ from each other as much as
+    /// possible to this file as a json array of arrays. This enumerates all minimum hitting sets.
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    diverse_optima: Option<PathBuf>,
+
+    /// Number of hitting sets to write with --diverse-optima
+    #[structopt(long, default_value = "5", value_name = "count")]
+    num_diverse_optima: usize,
+
+    /// Write, for each node of the final hitting set, the edges (by their index in the input) that no
+    /// other node of the hitting set hits to this file formatted as json
+    #[structopt(long, parse(from_os_str), value_name = "file")]
+    explain: Option<PathBuf>,
+
+    /// Check whether the minimum hitting set is unique by solving the instance once without each of
+    /// its nodes. The result is logged and included in the report.
+    #[structopt(long)]
+    check_unique: bool,
+
+    /// Replace the final hitting set by the lexicographically smallest minimum hitting set. This solves
+    /// the instance again up to once per node.
+    #[structopt(long)]
+    lexmin: bool,
+
+    /// Replace the final hitting set by a minimum hitting set whose maximum node degree is as small as
+    /// possible, avoiding high-degree nodes. This solves the instance a logarithmic number of times.
+    #[structopt(long, conflicts_with = "lexmin")]
+    min_max_degree: bool,
 }
 
 #[derive(Debug, StructOpt)]
@@ -114,12 +354,88 @@ struct ReduceOpts {
     #[structopt(short, long, parse(from_os_str), value_name = "file")]
     output: Option<PathBuf>,
 
-    /// Write the partial hitting set to this file as a json array
+    /// Write the partial hitting set and a fingerprint of the reduced hypergraph to this file as json
     #[structopt(short, long, parse(from_os_str), value_name = "file")]
     partial_solution: Option<PathBuf>,
+
+    /// Log why the edge with this index (counting from zero in input order) was removed or kept by
+    /// the reductions. Can be given multiple times.
+    #[structopt(long, value_name = "edge")]
+    explain_edge: Vec<usize>,
+}
+
+#[derive(Debug, StructOpt)]
+struct BoundsOpts {
+    #[structopt(flatten)]
+    common: CommonOpts,
+
+    /// Solver settings
+    #[structopt(parse(from_os_str), value_name = "settings-file")]
+    settings: PathBuf,
+}
+
+#[derive(Debug, StructOpt)]
+struct BenchOpts {
+    #[structopt(flatten)]
+    common: CommonOpts,
+
+    /// Solver settings
+    #[structopt(parse(from_os_str), value_name = "settings-file")]
+    settings: PathBuf,
+
+    /// Number of runs before the measured ones, which are not included in the statistics
+    #[structopt(long, default_value = "1", value_name = "count")]
+    warmup: usize,
+
+    /// Number of measured runs
+    #[structopt(long, default_value = "5", value_name = "count")]
+    runs: usize,
 }
 
-fn solve(opts: SolveOpts) -> Result<()> {
+#[derive(Debug, StructOpt)]
+struct EnumerateOpts {
+    #[structopt(flatten)]
+    common: CommonOpts,
+
+    /// Only enumerate hitting sets with at most this many nodes
+    #[structopt(short, long, value_name = "size")]
+    max_size: usize,
+
+    /// Stop after finding this many hitting sets
+    #[structopt(long, value_name = "count")]
+    max_count: Option<usize>,
+
+    /// Write the hitting sets to this file as a json array of arrays
+    #[structopt(short, long, parse(from_os_str), value_name = "file")]
+    output: Option<PathBuf>,
+
+    /// Write each hitting set to the output file as soon as it is found, as one json array per
+    /// line, instead of collecting them in memory first
+    #[structopt(long, requires("output"))]
+    stream: bool,
+}
+
+#[derive(Debug, StructOpt)]
+struct CleanOpts {
+    #[structopt(flatten)]
+    common: CommonOpts,
+
+    /// Write the hypergraph to this file instead of stdout
+    #[structopt(short, long, parse(from_os_str), value_name = "file")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+struct RankOpts {
+    #[structopt(flatten)]
+    common: CommonOpts,
+
+    /// Only list this many of the highest ranked nodes
+    #[structopt(long, value_name = "count")]
+    top: Option<usize>,
+}
+
+fn solve(opts: &SolveOpts) -> Result<()> {
     let file_name = opts
         .common
         .hypergraph
@@ -127,26 +443,433 @@ fn solve(opts: SolveOpts) -> Result<()> {
         .and_then(OsStr::to_str)
         .ok_or_else(|| anyhow!("File name can't be extracted"))?
         .to_string();
-    let instance = opts.common.load_instance()?;
-    let settings = {
-        let reader = BufReader::new(File::open(&opts.settings)?);
-        serde_json::from_reader(reader)?
+    let mut instance = opts.common.load_instance()?;
+    // Checked before ignoring edges or fixing nodes changes the instance
+    let partial_hs = if let Some(partial_solution_file) = &opts.reduced_from {
+        let reader = BufReader::new(File::open(partial_solution_file)?);
+        read_partial_hitting_set(&opts.common, reader, &instance)
+            .with_context(|| format!("in file {}", partial_solution_file.display()))?
+    } else {
+        Vec::new()
+    };
+    ignore_edges(&mut instance, &opts.ignore_edge)?;
+    let fixed_nodes = fix_nodes(&mut instance, &opts.common, &opts.fix_node)?;
+    let settings = read_solve_settings(opts)?;
+    let node_labels = if opts.labeled {
+        let node_labels = instance
+            .node_labels()
+            .ok_or_else(|| anyhow!("Instance has no node labels"))?;
+        Some(node_labels.to_vec())
+    } else {
+        None
     };
+    let num_nodes = instance.num_nodes_total();
+    let mut original_instance = (opts.proof.is_some()
+        || opts.optimal_nodes.is_some()
+        || opts.diverse_optima.is_some()
+        || opts.explain.is_some()
+        || opts.check_unique
+        || opts.lexmin
+        || opts.min_max_degree)
+        .then(|| instance.clone());
 
     info!("Solving {:?}", &opts.common.hypergraph);
-    let (final_hs, report) = solve::solve(instance, file_name, settings)?;
+    let (mut final_hs, mut report) = solve::solve(instance, file_name, settings)?;
+
+    if let Some(original_instance) = &mut original_instance {
+        refine_minimum(opts, original_instance, &mut final_hs, &mut report)?;
+        write_analyses(opts, original_instance, &final_hs, &report)?;
+    }
 
-    if let Some(solution_file) = opts.solution {
-        debug!("Writing solution to {}", solution_file.display());
-        let writer = BufWriter::new(File::create(&solution_file)?);
-        serde_json::to_writer(writer, &final_hs)?;
+    if !partial_hs.is_empty() {
+        info!(
+            "Adding {} nodes of the partial hitting set, final hitting set has size {}",
+            partial_hs.len(),
+            final_hs.len() + fixed_nodes.len() + partial_hs.len()
+        );
     }
-    if let Some(report_file) = opts.report {
+    // Like the fixed nodes, the nodes picked while reducing count towards the
+    // size of the final hitting set
+    report.opt += fixed_nodes.len() + partial_hs.len();
+    final_hs.extend(fixed_nodes);
+    final_hs.extend(partial_hs);
+
+    if let Some(solution_file) = &opts.solution {
+        write_solution(
+            opts,
+            solution_file,
+            &final_hs,
+            node_labels.as_deref(),
+            num_nodes,
+        )?;
+    }
+    if let Some(search_tree_file) = &opts.search_tree {
+        if let Some(mut search_tree) = report.search_tree.take() {
+            debug!("Writing search tree to {}", search_tree_file.display());
+            shift_search_tree(&opts.common, &mut search_tree);
+            let writer = BufWriter::new(File::create(search_tree_file)?);
+            serde_json::to_writer(writer, &search_tree)?;
+        } else {
+            warn!("No search tree was recorded, not writing it");
+        }
+    }
+    if let Some(report_file) = &opts.report {
         debug!("Writing report to {}", report_file.display());
-        let writer = BufWriter::new(File::create(&report_file)?);
+        let writer = BufWriter::new(File::create(report_file)?);
         serde_json::to_writer(writer, &report)?;
     }
+    if let Some(csv_file) = &opts.csv {
+        append_csv_row(csv_file, &report)?;
+    }
+
+    Ok(())
+}
+
+/// Deletes the edges given by `--ignore-edge`, numbered in input order.
+fn ignore_edges(instance: &mut Instance, input_edges: &[usize]) -> Result<()> {
+    for &edge_idx in input_edges {
+        let Some(edge) = instance.edge_from_input_index(edge_idx)? else {
+            debug!("Edge {} was skipped while loading", edge_idx);
+            continue;
+        };
+        if instance.is_edge_alive(edge) {
+            instance.delete_edge(edge);
+        }
+    }
+    if !input_edges.is_empty() {
+        info!(
+            "Ignoring {} edges, {} remain",
+            instance.num_deleted_edges(),
+            instance.num_edges()
+        );
+    }
+    Ok(())
+}
+
+/// Deletes the nodes given by `--fix-node` together with the edges they hit
+/// and returns them without duplicates.
+fn fix_nodes(
+    instance: &mut Instance,
+    common: &CommonOpts,
+    raw_nodes: &[usize],
+) -> Result<Vec<NodeIdx>> {
+    let mut fixed_nodes = common.input_indices(raw_nodes)?;
+    fixed_nodes.sort_unstable();
+    fixed_nodes.dedup();
+    for &node in &fixed_nodes {
+        let _ = instance.try_node(node)?;
+        instance.delete_node(node);
+        instance.delete_incident_edges(node);
+    }
+    if !fixed_nodes.is_empty() {
+        info!(
+            "Fixed {} nodes, {} edges are not hit by them",
+            fixed_nodes.len(),
+            instance.num_edges()
+        );
+    }
+    Ok(fixed_nodes)
+}
+
+/// Reads the settings file and applies the command line options overriding
+/// single settings.
+fn read_solve_settings(opts: &SolveOpts) -> Result<Settings> {
+    let mut settings: Settings = {
+        let reader = BufReader::new(File::open(&opts.settings)?);
+        serde_json::from_reader(reader)?
+    };
+    if opts.search_tree.is_some() {
+        settings.search_tree_depth = Some(opts.search_tree_depth);
+    }
+    if let Some(initial_solution_file) = &opts.initial_solution {
+        debug!(
+            "Reading initial hitting set from {}",
+            initial_solution_file.display()
+        );
+        let reader = BufReader::new(File::open(initial_solution_file)?);
+        let initial_hs = read_solution(&opts.common, reader)
+            .with_context(|| format!("in file {}", initial_solution_file.display()))?;
+        settings.initial_hitting_set = Some(initial_hs.into_iter().map(u32::from).collect());
+    }
+    Ok(settings)
+}
+
+/// Reads a solution written by `solve` as a json array of node indices.
+fn read_solution(common: &CommonOpts, reader: impl io::Read) -> Result<Vec<NodeIdx>> {
+    let raw_hs: Vec<usize> = serde_json::from_reader(reader)?;
+    common.input_indices(&raw_hs)
+}
+
+/// Partial hitting set written by `reduce`, along with the fingerprint of the
+/// reduced instance it belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+struct PartialSolution {
+    nodes: Vec<usize>,
+    fingerprint: u64,
+}
+
+fn write_partial_hitting_set(
+    common: &CommonOpts,
+    writer: impl Write,
+    partial_hs: &[NodeIdx],
+    reduced_instance: &Instance,
+) -> Result<()> {
+    let partial_solution = PartialSolution {
+        nodes: common.output_indices(partial_hs),
+        fingerprint: reduced_instance.fingerprint(),
+    };
+    serde_json::to_writer(writer, &partial_solution)?;
+    Ok(())
+}
+
+/// Shifts the node indices in the search tree like `output_indices`.
+fn shift_search_tree(common: &CommonOpts, search_tree: &mut SearchTreeNode) {
+    search_tree.node = search_tree
+        .node
+        .map(|node| NodeIdx::from(common.output_indices(&[node])[0]));
+    for child in &mut search_tree.children {
+        shift_search_tree(common, child);
+    }
+}
+
+/// Reads the partial hitting set written by `reduce` and checks that it
+/// matches the reduced instance.
+fn read_partial_hitting_set(
+    common: &CommonOpts,
+    reader: impl io::Read,
+    instance: &Instance,
+) -> Result<Vec<NodeIdx>> {
+    let partial_solution: PartialSolution = serde_json::from_reader(reader)?;
+    ensure!(
+        partial_solution.fingerprint == instance.fingerprint(),
+        "the instance does not match the reduction, its fingerprint differs"
+    );
+    let raw_partial_hs = partial_solution.nodes;
+    let partial_hs = common.input_indices(&raw_partial_hs)?;
+    let mut is_chosen = vec![false; instance.num_nodes_total()];
+    for (&node, &raw_node) in partial_hs.iter().zip(&raw_partial_hs) {
+        // Nodes beyond the last one of the reduced instance are in no edge
+        let Ok(mut incident_edges) = instance.try_node(node) else {
+            continue;
+        };
+        // The reductions delete the nodes they pick together with all
+        // edges these nodes hit, so none of them can remain in an edge
+        ensure!(
+            incident_edges.next().is_none(),
+            "node {} of the partial hitting set is still contained in a hyperedge, the \
+             instance does not match the reduction",
+            raw_node
+        );
+        ensure!(
+            !is_chosen[node.idx()],
+            "node {} occurs twice in the partial hitting set",
+            raw_node
+        );
+        is_chosen[node.idx()] = true;
+    }
+    Ok(partial_hs)
+}
+
+/// Replaces or checks a proven minimum hitting set as requested by
+/// `--lexmin`, `--min-max-degree` and `--check-unique`.
+fn refine_minimum(
+    opts: &SolveOpts,
+    instance: &Instance,
+    final_hs: &mut Vec<NodeIdx>,
+    report: &mut Report,
+) -> Result<()> {
+    if opts.lexmin {
+        if report.proven_optimal {
+            *final_hs =
+                solve::lexicographically_smallest_minimum(instance, report.opt, &report.settings)?;
+        } else {
+            warn!("Hitting set is not proven to be minimum, not searching for the smallest one");
+        }
+    }
+
+    if opts.min_max_degree {
+        if report.proven_optimal {
+            *final_hs = solve::min_max_degree_minimum(instance, final_hs, &report.settings)?;
+        } else {
+            warn!("Hitting set is not proven to be minimum, not minimizing its maximum degree");
+        }
+    }
 
+    if opts.check_unique {
+        if report.proven_optimal {
+            let unique = solve::is_unique_minimum(instance, final_hs, &report.settings)?;
+            info!("Minimum hitting set is unique: {}", unique);
+            report.unique_minimum = Some(unique);
+        } else {
+            warn!("Hitting set is not proven to be minimum, not checking uniqueness");
+        }
+    }
+    Ok(())
+}
+
+/// Writes the outputs that analyse the found hitting set on the instance it
+/// was computed for: the proof, the explanation, diverse optima and the
+/// classification of optimal nodes.
+fn write_analyses(
+    opts: &SolveOpts,
+    instance: &mut Instance,
+    final_hs: &[NodeIdx],
+    report: &Report,
+) -> Result<()> {
+    if let Some(proof_file) = &opts.proof {
+        if let Some(proof) = Proof::find(
+            instance,
+            final_hs.to_vec(),
+            &report.settings,
+            opts.proof_gap,
+        ) {
+            if proof.lower_bound < proof.solution.len() {
+                warn!(
+                    "Proof only certifies a lower bound of {} for a hitting set of size {}",
+                    proof.lower_bound,
+                    proof.solution.len()
+                );
+            }
+            ensure!(proof.verify(instance), "generated proof is invalid");
+            debug!("Writing proof to {}", proof_file.display());
+            let writer = BufWriter::new(File::create(proof_file)?);
+            serde_json::to_writer(
+                writer,
+                &serde_json::json!({
+                    "solution": opts.common.output_indices(&proof.solution),
+                    "lower_bound": proof.lower_bound,
+                    "lower_bound_witness": proof.lower_bound_witness,
+                }),
+            )?;
+        } else {
+            warn!("No packing proving the optimality of the hitting set was found");
+        }
+    }
+
+    if let Some(explain_file) = &opts.explain {
+        write_explanation(&opts.common, explain_file, instance, final_hs)?;
+    }
+
+    if let Some(diverse_optima_file) = &opts.diverse_optima {
+        if report.proven_optimal {
+            let diverse_optima = enumerate::diverse_minimum_hitting_sets(
+                instance,
+                report.opt,
+                opts.num_diverse_optima,
+            );
+            debug!(
+                "Writing diverse optima to {}",
+                diverse_optima_file.display()
+            );
+            let writer = BufWriter::new(File::create(diverse_optima_file)?);
+            let diverse_optima: Vec<_> = diverse_optima
+                .iter()
+                .map(|hs| opts.common.output_indices(hs))
+                .collect();
+            serde_json::to_writer(writer, &diverse_optima)?;
+        } else {
+            warn!("Hitting set is not proven to be minimum, not searching for diverse ones");
+        }
+    }
+
+    if let Some(optimal_nodes_file) = &opts.optimal_nodes {
+        if report.proven_optimal {
+            let (essential, optional) = enumerate::classify_optimal_nodes(instance, report.opt);
+            debug!("Writing optimal nodes to {}", optimal_nodes_file.display());
+            let writer = BufWriter::new(File::create(optimal_nodes_file)?);
+            let optimal_nodes = serde_json::json!({
+                "essential": opts.common.output_indices(&essential),
+                "optional": opts.common.output_indices(&optional),
+            });
+            serde_json::to_writer(writer, &optimal_nodes)?;
+        } else {
+            warn!("Hitting set is not proven to be minimum, not classifying optimal nodes");
+        }
+    }
+    Ok(())
+}
+
+/// Writes the critical edges of every node of the hitting set, numbered in
+/// input order.
+fn write_explanation(
+    common: &CommonOpts,
+    explain_file: &Path,
+    instance: &Instance,
+    final_hs: &[NodeIdx],
+) -> Result<()> {
+    let critical_edges = instance.critical_edges(final_hs);
+    let explanation: Vec<_> = common
+        .output_indices(final_hs)
+        .into_iter()
+        .zip(critical_edges)
+        .map(|(node, edges)| {
+            if edges.is_empty() {
+                warn!("Node {} of the hitting set is redundant", node);
+            }
+            let edges: Vec<_> = edges
+                .into_iter()
+                .map(|edge| instance.input_edge_index(edge))
+                .collect();
+            serde_json::json!({ "node": node, "critical_edges": edges })
+        })
+        .collect();
+    debug!("Writing explanation to {}", explain_file.display());
+    let writer = BufWriter::new(File::create(explain_file)?);
+    serde_json::to_writer(writer, &explanation)?;
+    Ok(())
+}
+
+/// Marks the nodes of the hitting set in a vector with one entry per node.
+fn indicator_vector(hs: &[NodeIdx], num_nodes: usize) -> Vec<bool> {
+    let mut indicator = vec![false; num_nodes];
+    for node in hs {
+        indicator[node.idx()] = true;
+    }
+    indicator
+}
+
+/// Writes the final hitting set as node indices, node labels or an indicator
+/// vector.
+fn write_solution(
+    opts: &SolveOpts,
+    solution_file: &Path,
+    final_hs: &[NodeIdx],
+    node_labels: Option<&[String]>,
+    num_nodes: usize,
+) -> Result<()> {
+    debug!("Writing solution to {}", solution_file.display());
+    let writer = BufWriter::new(File::create(solution_file)?);
+    if let Some(node_labels) = node_labels {
+        let labels: Vec<_> = final_hs
+            .iter()
+            .map(|node| &node_labels[node.idx()])
+            .collect();
+        serde_json::to_writer(writer, &labels)?;
+    } else if opts.indicator {
+        serde_json::to_writer(writer, &indicator_vector(final_hs, num_nodes))?;
+    } else {
+        serde_json::to_writer(writer, &opts.common.output_indices(final_hs))?;
+    }
+    Ok(())
+}
+
+/// Appends the report as a row to the CSV file, starting the file with a
+/// header if it is empty.
+fn append_csv_row(csv_file: &Path, report: &Report) -> Result<()> {
+    debug!("Appending csv row to {}", csv_file.display());
+    let file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(csv_file)?;
+    let mut header = String::new();
+    BufReader::new(&file).read_line(&mut header)?;
+    let mut writer = BufWriter::new(file);
+    if header.is_empty() {
+        header = Report::csv_header();
+        writeln!(writer, "{header}")?;
+    }
+    writeln!(writer, "{}", report.to_csv_row(header.trim_end()))?;
     Ok(())
 }
 
@@ -164,22 +887,235 @@ fn reduce(opts: ReduceOpts) -> Result<()> {
         serde_json::from_reader(reader)?
     };
 
+    let original_instance = (!opts.explain_edge.is_empty()).then(|| instance.clone());
+
     info!("Reducing {:?}", &opts.common.hypergraph);
-    let (reduced_instance, _reduction_result, partial_hs) =
-        solve::only_reduce(instance, file_name, settings)?;
+    let (reduced_instance, partial_hs) = reduce_to_kernel(instance, file_name, settings)?;
+    info!(
+        "Reductions removed {} nodes and {} edges",
+        reduced_instance.num_deleted_nodes(),
+        reduced_instance.num_deleted_edges()
+    );
+
+    if let Some(original_instance) = original_instance {
+        for &edge_idx in &opts.explain_edge {
+            let Some(edge) = original_instance.edge_from_input_index(edge_idx)? else {
+                info!("Edge {} was skipped while loading", edge_idx);
+                continue;
+            };
+            if let Some(node) = original_instance
+                .try_edge(edge)?
+                .find(|node| partial_hs.contains(node))
+            {
+                info!(
+                    "Edge {} was removed since it is hit by node {} of the partial hitting set",
+                    edge_idx,
+                    opts.common.output_indices(&[node])[0]
+                );
+            } else if !reduced_instance.is_edge_alive(edge) {
+                info!(
+                    "Edge {} was removed since it contains another edge",
+                    edge_idx
+                );
+            } else if let Some(other_edge) =
+                reductions::find_dominating_edge(&reduced_instance, edge)
+            {
+                info!(
+                    "Edge {} was kept, but contains edge {} and can be removed",
+                    edge_idx,
+                    reduced_instance.input_edge_index(other_edge)
+                );
+            } else {
+                info!(
+                    "Edge {} was kept since no other remaining edge is a subset of it",
+                    edge_idx
+                );
+            }
+        }
+    }
 
     if let Some(instance_file) = opts.output {
         debug!("Writing remaining instance to {}", instance_file.display());
         let writer = BufWriter::new(File::create(&instance_file)?);
-        reduced_instance.export_as_text(writer)?;
+        reduced_instance.export_as_text(writer, &opts.common.export_options())?;
     }
 
     if let Some(partial_solution_file) = opts.partial_solution {
         debug!("Writing solution to {}", partial_solution_file.display());
         let writer = BufWriter::new(File::create(&partial_solution_file)?);
-        serde_json::to_writer(writer, &partial_hs)?;
+        write_partial_hitting_set(&opts.common, writer, &partial_hs, &reduced_instance)?;
+    }
+
+    Ok(())
+}
+
+/// Applies the reductions at the root and returns the kernel with the nodes
+/// picked by them.
+///
+/// If the bounds already prune the root or the stopping size is reached, the
+/// remaining instance is no kernel whose minimum hitting sets complete the
+/// partial one. The unreduced instance is returned instead, as in
+/// `solve_compacted`.
+fn reduce_to_kernel(
+    instance: Instance,
+    file_name: String,
+    settings: Settings,
+) -> Result<(Instance, Vec<NodeIdx>)> {
+    let (reduced_instance, reduction_result, partial_hs) =
+        solve::only_reduce(instance.clone(), file_name, settings)?;
+    if matches!(
+        reduction_result,
+        ReductionResult::Solved | ReductionResult::Finished
+    ) {
+        Ok((reduced_instance, partial_hs))
+    } else {
+        warn!("Reductions ended the search at the root, keeping the unreduced instance");
+        Ok((instance, Vec::new()))
+    }
+}
+
+fn bounds(opts: &BoundsOpts) -> Result<()> {
+    let instance = opts.common.load_instance()?;
+    let settings = {
+        let reader = BufReader::new(File::open(&opts.settings)?);
+        serde_json::from_reader(reader)?
+    };
+
+    info!("Calculating bounds for {:?}", &opts.common.hypergraph);
+    let root_bounds = solve::only_bounds(&instance, &settings);
+    info!(
+        "Lower bound is {}, greedy upper bound is {}",
+        root_bounds.lower_bound(),
+        root_bounds.greedy_upper
+    );
+
+    let stdout = io::stdout();
+    serde_json::to_writer(stdout.lock(), &root_bounds)?;
+    println!();
+    Ok(())
+}
+
+fn rank(opts: &RankOpts) -> Result<()> {
+    let instance = opts.common.load_instance()?;
+
+    info!("Ranking nodes of {:?}", &opts.common.hypergraph);
+    let mut ranking = lower_bound::rank_nodes(&instance);
+    if let Some(top) = opts.top {
+        ranking.truncate(top);
     }
+    let ranking: Vec<_> = ranking
+        .into_iter()
+        .map(|(node, score)| (opts.common.output_indices(&[node])[0], score))
+        .collect();
+
+    let stdout = io::stdout();
+    serde_json::to_writer(stdout.lock(), &ranking)?;
+    println!();
+    Ok(())
+}
+
+fn bench(opts: &BenchOpts) -> Result<()> {
+    let file_name = opts
+        .common
+        .hypergraph
+        .file_name()
+        .and_then(OsStr::to_str)
+        .ok_or_else(|| anyhow!("File name can't be extracted"))?;
+    let instance = opts.common.load_instance()?;
+    let settings: Settings = {
+        let reader = BufReader::new(File::open(&opts.settings)?);
+        serde_json::from_reader(reader)?
+    };
+
+    let stats = solve::bench_runs(&instance, file_name, &settings, opts.warmup, opts.runs)?;
+    info!(
+        "Runtime over {} runs: min {:.2?}, median {:.2?}, max {:.2?}",
+        stats.runs, stats.min_runtime, stats.median_runtime, stats.max_runtime
+    );
 
+    let stdout = io::stdout();
+    serde_json::to_writer(stdout.lock(), &stats)?;
+    println!();
+    Ok(())
+}
+
+fn enumerate(opts: EnumerateOpts) -> Result<()> {
+    let mut instance = opts.common.load_instance()?;
+
+    info!(
+        "Enumerating minimal hitting sets of {:?}",
+        &opts.common.hypergraph
+    );
+    if opts.stream {
+        return enumerate_streaming(&mut instance, &opts);
+    }
+    let (minimal_hs, truncated) =
+        enumerate::enumerate_minimal_hitting_sets(&mut instance, opts.max_size, opts.max_count);
+    if truncated {
+        warn!(
+            "There are more than {} minimal hitting sets",
+            minimal_hs.len()
+        );
+    }
+
+    if let Some(output_file) = opts.output {
+        debug!("Writing hitting sets to {}", output_file.display());
+        let writer = BufWriter::new(File::create(&output_file)?);
+        let minimal_hs: Vec<_> = minimal_hs
+            .iter()
+            .map(|hs| opts.common.output_indices(hs))
+            .collect();
+        serde_json::to_writer(writer, &minimal_hs)?;
+    }
+
+    Ok(())
+}
+
+fn enumerate_streaming(instance: &mut Instance, opts: &EnumerateOpts) -> Result<()> {
+    let output_file = opts
+        .output
+        .as_ref()
+        .expect("Streaming requires an output file");
+    debug!("Streaming hitting sets to {}", output_file.display());
+    let mut writer = BufWriter::new(File::create(output_file)?);
+    let max_count = opts.max_count.unwrap_or(usize::MAX);
+    let mut num_found = 0;
+    let mut write_result = Ok(());
+    let truncated = enumerate::for_each_minimal_hitting_set(instance, opts.max_size, |hs| {
+        if num_found >= max_count {
+            return ControlFlow::Break(());
+        }
+        let line = serde_json::to_string(&opts.common.output_indices(hs))
+            .expect("Serializing node indices can't fail");
+        write_result = writeln!(writer, "{line}");
+        if write_result.is_err() {
+            return ControlFlow::Break(());
+        }
+        num_found += 1;
+        ControlFlow::Continue(())
+    });
+    write_result?;
+    writer.flush()?;
+
+    info!("Found {} minimal hitting sets", num_found);
+    if truncated {
+        warn!("There are more than {} minimal hitting sets", num_found);
+    }
+    Ok(())
+}
+
+fn clean(opts: &CleanOpts) -> Result<()> {
+    let instance = opts.common.load_instance()?;
+    let export_options = opts.common.export_options();
+    if let Some(output_file) = &opts.output {
+        debug!("Writing hypergraph to {}", output_file.display());
+        let mut writer = BufWriter::new(File::create(output_file)?);
+        instance.export_as_canonical_text(&mut writer, &export_options)?;
+        writer.flush()?;
+    } else {
+        let stdout = io::stdout();
+        instance.export_as_canonical_text(stdout.lock(), &export_options)?;
+    }
     Ok(())
 }
 
@@ -201,7 +1137,11 @@ fn convert_to_ilp(opts: IlpOpts) -> Result<()> {
     }
 
     let stdout = io::stdout();
-    instance.export_as_ilp(stdout.lock())
+    if opts.opb {
+        instance.export_as_opb(stdout.lock())
+    } else {
+        instance.export_as_ilp(stdout.lock())
+    }
 }
 
 fn main() -> Result<()> {
@@ -211,8 +1151,228 @@ fn main() -> Result<()> {
 
     let opts = CliOpts::from_args();
     match opts {
-        CliOpts::Solve(solve_opts) => solve(solve_opts),
+        CliOpts::Solve(solve_opts) => solve(&solve_opts),
         CliOpts::Ilp(ilp_opts) => convert_to_ilp(ilp_opts),
         CliOpts::Reduce(reduce_opts) => reduce(reduce_opts),
+        CliOpts::Enumerate(enumerate_opts) => enumerate(enumerate_opts),
+        CliOpts::Bounds(bounds_opts) => bounds(&bounds_opts),
+        CliOpts::Bench(bench_opts) => bench(&bench_opts),
+        CliOpts::Clean(clean_opts) => clean(&clean_opts),
+        CliOpts::Rank(rank_opts) => rank(&rank_opts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn common_opts(args: &[&str]) -> CommonOpts {
+        CommonOpts::from_iter(["findminhs", "input.hgr"].iter().chain(args))
+    }
+
+    #[test]
+    fn input_indices_reject_overflow() {
+        let opts = common_opts(&[]);
+        assert_eq!(
+            opts.input_indices(&[0, 7]).unwrap(),
+            vec![NodeIdx::from(0_u32), NodeIdx::from(7_u32)]
+        );
+        assert!(opts.input_indices(&[1 << 32]).is_err());
+        assert!(opts.input_indices(&[u32::MAX as usize]).is_err());
+    }
+
+    #[test]
+    fn input_indices_reject_underflow() {
+        let opts = common_opts(&["--one-indexed"]);
+        assert_eq!(
+            opts.input_indices(&[1]).unwrap(),
+            vec![NodeIdx::from(0_u32)]
+        );
+        assert!(opts.input_indices(&[0]).is_err());
+    }
+
+    #[test]
+    fn saved_reduction_solves_like_the_original() {
+        let settings =
+            || -> Settings { serde_json::from_str(include_str!("../settings.json")).unwrap() };
+        let load =
+            |text: &[u8], options: &LoadOptions| Instance::load_from_text(text, options).unwrap();
+        let opts = common_opts(&[]);
+        let kernel_options = LoadOptions {
+            no_degree_prefix: true,
+            infer_node_count: true,
+            ..LoadOptions::default()
+        };
+        // The unit edge forces node 0, the bounds prune the root of the second
+        // instance, so that it is kept unreduced
+        let petersen_with_unit_edge = "10 16\n1 0\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n\
+                                       2 0 5\n2 1 6\n2 2 7\n2 3 8\n2 4 9\n2 5 7\n2 6 8\n\
+                                       2 7 9\n2 5 8\n2 6 9\n";
+        let pruned_at_root = "13 25\n4 2 7 9 11\n4 0 2 11 12\n3 0 3 12\n2 8 12\n2 0 11\n\
+                              4 1 7 8 12\n4 2 6 8 11\n3 1 6 11\n2 3 12\n3 0 1 8\n\
+                              4 0 5 8 10\n2 2 7\n4 2 5 8 10\n2 7 9\n2 6 10\n3 5 8 9\n\
+                              3 7 9 10\n4 3 4 7 8\n2 9 12\n3 4 6 8\n4 0 2 10 11\n2 0 12\n\
+                              3 3 4 8\n4 1 2 8 10\n3 1 9 11\n";
+        for (text, num_forced) in [(petersen_with_unit_edge, 1), (pruned_at_root, 0)] {
+            let instance = load(text.as_bytes(), &LoadOptions::default());
+            let (direct_hs, _) = solve::solve(instance.clone(), String::new(), settings()).unwrap();
+            let (kernel, partial_hs) =
+                reduce_to_kernel(instance, String::new(), settings()).unwrap();
+            assert_eq!(partial_hs.len(), num_forced);
+
+            let (mut kernel_text, mut partial_text) = (Vec::new(), Vec::new());
+            kernel
+                .export_as_text(&mut kernel_text, &opts.export_options())
+                .unwrap();
+            write_partial_hitting_set(&opts, &mut partial_text, &partial_hs, &kernel).unwrap();
+            let reloaded = load(&kernel_text, &kernel_options);
+            let reloaded_partial_hs =
+                read_partial_hitting_set(&opts, &partial_text[..], &reloaded).unwrap();
+            assert_eq!(reloaded_partial_hs, partial_hs);
+            let (kernel_hs, report) = solve::solve(reloaded, String::new(), settings()).unwrap();
+            assert!(report.proven_optimal);
+            assert_eq!(kernel_hs.len() + partial_hs.len(), direct_hs.len());
+        }
+
+        // A partial hitting set does not fit a different instance
+        let instance = load(petersen_with_unit_edge.as_bytes(), &LoadOptions::default());
+        let (kernel, partial_hs) = reduce_to_kernel(instance, String::new(), settings()).unwrap();
+        let mut partial_text = Vec::new();
+        write_partial_hitting_set(&opts, &mut partial_text, &partial_hs, &kernel).unwrap();
+        let other = load(pruned_at_root.as_bytes(), &LoadOptions::default());
+        assert!(read_partial_hitting_set(&opts, &partial_text[..], &other).is_err());
+    }
+
+    #[test]
+    fn one_indexed_search_tree_shifts_all_nodes() {
+        fn nodes(search_tree: &SearchTreeNode, nodes_found: &mut Vec<Option<usize>>) {
+            nodes_found.push(search_tree.node.map(|node| node.idx()));
+            for child in &search_tree.children {
+                nodes(child, nodes_found);
+            }
+        }
+
+        let settings: Settings = serde_json::from_str(include_str!("../settings.json")).unwrap();
+        let petersen = "10 15\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n2 0 5\n2 1 6\n2 2 7\n\
+                        2 3 8\n2 4 9\n2 5 7\n2 6 8\n2 7 9\n2 5 8\n2 6 9\n";
+        let instance = Instance::load_from_text(petersen.as_bytes(), &LoadOptions::default());
+        let tree_settings = Settings {
+            search_tree_depth: Some(usize::MAX),
+            ..settings
+        };
+        let (_, report) = solve::solve(instance.unwrap(), String::new(), tree_settings).unwrap();
+        let search_tree = report.search_tree.unwrap();
+        let mut shifted = search_tree.clone();
+        shift_search_tree(&common_opts(&["--one-indexed"]), &mut shifted);
+
+        let (mut before, mut after) = (Vec::new(), Vec::new());
+        nodes(&search_tree, &mut before);
+        nodes(&shifted, &mut after);
+        assert!(before.len() > 1);
+        assert_eq!(after[0], None);
+        let expected: Vec<_> = before.iter().map(|node| node.map(|idx| idx + 1)).collect();
+        assert_eq!(after, expected);
+    }
+
+    #[test]
+    fn indicator_vector_marks_hitting_set_nodes() {
+        let hs = [NodeIdx::from(3_u32), NodeIdx::from(0_u32)];
+        let indicator = indicator_vector(&hs, 5);
+        assert_eq!(indicator, [true, false, false, true, false]);
+        assert_eq!(indicator.iter().filter(|&&in_hs| in_hs).count(), hs.len());
+        assert!(indicator_vector(&[], 2).iter().all(|&in_hs| !in_hs));
+    }
+
+    #[test]
+    fn piped_input_is_loaded_plain_or_compressed() {
+        let opts = common_opts(&[]);
+        let text = "3 2\n2 0 1\n2 1 2\n";
+        let instance = opts.load_reader(Box::new(text.as_bytes())).unwrap();
+        assert_eq!(instance.num_nodes_total(), 3);
+        assert_eq!(instance.num_edges(), 2);
+
+        // The same hypergraph compressed with gzip
+        let compressed: &[u8] = &[
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x33, 0x56, 0x30, 0xe2,
+            0x32, 0x52, 0x30, 0x50, 0x30, 0x04, 0x92, 0x86, 0x40, 0x36, 0x00, 0x76, 0xa4, 0xde,
+            0x40, 0x10, 0x00, 0x00, 0x00,
+        ];
+        let decompressed = opts.load_reader(Box::new(compressed)).unwrap();
+        assert_eq!(decompressed.num_nodes_total(), 3);
+        assert_eq!(decompressed.num_edges(), 2);
+        assert!(opts
+            .load_reader(Box::new("3 2\n2 0 1\n".as_bytes()))
+            .is_err());
+    }
+
+    #[test]
+    fn ignored_edges_need_not_be_hit() {
+        use crate::instance::EdgeIdx;
+
+        let settings: Settings = serde_json::from_str(include_str!("../settings.json")).unwrap();
+        let text = "5 3\n2 0 1\n2 0 2\n2 3 4\n";
+        let load = || Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let mut instance = load();
+        ignore_edges(&mut instance, &[2]).unwrap();
+        assert_eq!(instance.num_edges(), 2);
+        let (hs, _) = solve::solve(instance, String::new(), settings).unwrap();
+        assert_eq!(hs, [NodeIdx::from(0_u32)]);
+        let original = load();
+        assert!(!original
+            .edge(EdgeIdx::from(2_u32))
+            .any(|node| hs.contains(&node)));
+
+        assert!(ignore_edges(&mut load(), &[3]).is_err());
+    }
+
+    #[test]
+    fn fixed_nodes_are_completed_minimally() {
+        let settings =
+            || -> Settings { serde_json::from_str(include_str!("../settings.json")).unwrap() };
+        let text = "4 3\n2 0 1\n2 1 2\n2 2 3\n";
+        let load = || Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let opts = common_opts(&[]);
+
+        // Fixing an end of the path leaves only the other two edges
+        let mut instance = load();
+        let fixed_nodes = fix_nodes(&mut instance, &opts, &[3, 3]).unwrap();
+        assert_eq!(fixed_nodes, [NodeIdx::from(3_u32)]);
+        assert_eq!(instance.num_edges(), 2);
+        let (mut hs, _) = solve::solve(instance, String::new(), settings()).unwrap();
+        assert_eq!(hs, [NodeIdx::from(1_u32)]);
+        hs.extend(fixed_nodes);
+        assert!(solve::is_hitting_set(&hs, &load()));
+
+        // Fixing both ends needs one more node than a minimum hitting set
+        let mut instance = load();
+        let fixed_nodes = fix_nodes(&mut instance, &opts, &[0, 3]).unwrap();
+        let (hs, _) = solve::solve(instance, String::new(), settings()).unwrap();
+        assert_eq!(hs.len() + fixed_nodes.len(), 3);
+
+        assert!(fix_nodes(&mut load(), &opts, &[4]).is_err());
+    }
+
+    #[test]
+    fn written_solution_is_a_warm_start() {
+        let settings =
+            || -> Settings { serde_json::from_str(include_str!("../settings.json")).unwrap() };
+        let text = "5 5\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n";
+        let load = || Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let opts = common_opts(&["--one-indexed"]);
+        let (hs, _) = solve::solve(load(), String::new(), settings()).unwrap();
+        let written = serde_json::to_vec(&opts.output_indices(&hs)).unwrap();
+
+        let read_hs = read_solution(&opts, &written[..]).unwrap();
+        assert_eq!(read_hs, hs);
+        let warm_settings = Settings {
+            initial_hitting_set: Some(read_hs.into_iter().map(u32::from).collect()),
+            ..settings()
+        };
+        let (warm_hs, report) = solve::solve(load(), String::new(), warm_settings).unwrap();
+        assert_eq!(warm_hs.len(), hs.len());
+        assert!(report.upper_bound_improvements.is_empty());
+
+        // Index 0 does not exist when counting from one
+        assert!(read_solution(&opts, &b"[0, 2]"[..]).is_err());
     }
 }
@@ -0,0 +1,96 @@
+use crate::{
+    instance::{EdgeIdx, Instance, NodeIdx},
+    lower_bound::PackingBound,
+    report::Settings,
+    small_indices::{IdxHashSet, SmallIdx},
+    solve,
+};
+use log::info;
+use serde::Serialize;
+
+/// Independently checkable reason why no hitting set can be smaller than a
+/// given size.
+#[derive(Debug, Clone, Serialize)]
+pub enum LowerBoundWitness {
+    /// Pairwise disjoint edges, each of which needs its own node in any
+    /// hitting set.
+    Packing(Vec<EdgeIdx>),
+}
+
+impl LowerBoundWitness {
+    fn verify(&self, instance: &Instance) -> Option<usize> {
+        match self {
+            Self::Packing(packing) => {
+                let mut hit = vec![false; instance.num_nodes_total()];
+                let mut seen_edges = IdxHashSet::default();
+                for &edge in packing {
+                    if edge.idx() >= instance.num_edges_total() || !seen_edges.insert(edge) {
+                        return None;
+                    }
+                    for node in instance.edge(edge) {
+                        if hit[node.idx()] {
+                            return None;
+                        }
+                        hit[node.idx()] = true;
+                    }
+                }
+                Some(packing.len())
+            }
+        }
+    }
+}
+
+/// A hitting set together with a witness for a lower bound on the size of
+/// any hitting set. If both are equal, the hitting set is proven minimum.
+#[derive(Debug, Clone, Serialize)]
+pub struct Proof {
+    pub solution: Vec<NodeIdx>,
+    pub lower_bound: usize,
+    pub lower_bound_witness: LowerBoundWitness,
+}
+
+impl Proof {
+    /// Tries to find a witness for the minimality of the given hitting set.
+    ///
+    /// Only packing witnesses are supported, so this fails whenever no
+    /// packing of the same size as the hitting set is found, even if the
+    /// hitting set is in fact minimum. With `allow_gap`, the largest packing
+    /// found is used regardless, certifying the remaining gap instead.
+    pub fn find(
+        instance: &Instance,
+        solution: Vec<NodeIdx>,
+        settings: &Settings,
+        allow_gap: bool,
+    ) -> Option<Self> {
+        let packing = PackingBound::new(instance, settings);
+        info!(
+            "Found packing of size {} for hitting set of size {}",
+            packing.bound(),
+            solution.len()
+        );
+        if allow_gap || packing.bound() == solution.len() {
+            Some(Self {
+                solution,
+                lower_bound: packing.bound(),
+                lower_bound_witness: LowerBoundWitness::Packing(packing.packing().to_vec()),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the solution is a hitting set of the instance and that the
+    /// witness proves the claimed lower bound.
+    pub fn verify(&self, instance: &Instance) -> bool {
+        let solution_is_valid = self
+            .solution
+            .iter()
+            .all(|&node| node.idx() < instance.num_nodes_total())
+            && solve::is_hitting_set(&self.solution, instance);
+        let solution_set: IdxHashSet<_> = self.solution.iter().copied().collect();
+        solution_is_valid
+            && solution_set.len() == self.solution.len()
+            && self.lower_bound <= self.solution.len()
+            && self.lower_bound_witness.verify(instance) == Some(self.lower_bound)
+    }
+}
@@ -2,14 +2,15 @@ use crate::{
     data_structures::{subset_trie::SubsetTrie, superset_trie::SupersetTrie},
     instance::{EdgeIdx, Instance, NodeIdx},
     lower_bound::{self, EfficiencyBound, PackingBound},
-    report::{GreedyMode, Report, Settings, UpperBoundImprovement},
-    small_indices::{IdxHashSet, SmallIdx},
+    report::{GreedyMode, GreedyStrategy, Report, Settings, UpperBoundImprovement},
+    small_indices::SmallIdx,
     solve::State,
 };
 use log::info;
 use std::{
     cmp::Reverse,
     collections::BinaryHeap,
+    mem,
     time::{Duration, Instant},
 };
 use std::sync::atomic::Ordering;
@@ -52,6 +53,15 @@ impl ReducedItem {
 pub struct Reduction(Vec<ReducedItem>);
 
 impl Reduction {
+    pub fn num_items(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Number of bytes allocated to store the reduced items.
+    pub fn heap_size(&self) -> usize {
+        self.0.capacity() * mem::size_of::<ReducedItem>()
+    }
+
     pub fn restore(&self, instance: &mut Instance, partial_hs: &mut Vec<NodeIdx>) {
         for item in self.0.iter().rev() {
             item.restore(instance, partial_hs);
@@ -80,6 +90,11 @@ pub enum ReductionResult {
     Finished,
 }
 
+/// Finds nodes whose incident edges are a subset of those of another node.
+///
+/// Any hitting set using such a node stays valid when it is swapped for the
+/// dominating node, so it can be removed. This covers both the strict subset
+/// case and twins with identical incidences, of which only one is kept.
 fn find_dominated_nodes(instance: &Instance) -> impl Iterator<Item = ReducedItem> + '_ {
     let mut nodes = instance.nodes().to_vec();
     nodes.sort_unstable_by_key(|&node| Reverse(instance.node_degree(node)));
@@ -95,8 +110,7 @@ fn find_dominated_nodes(instance: &Instance) -> impl Iterator<Item = ReducedItem
 }
 
 fn find_dominated_edges(instance: &Instance) -> impl Iterator<Item = ReducedItem> + '_ {
-    let mut edges = instance.edges().to_vec();
-    edges.sort_unstable_by_key(|&edge| instance.edge_size(edge));
+    let edges = instance.edges_sorted_by_size();
     let mut trie = SubsetTrie::new(instance.num_nodes_total());
     edges.into_iter().filter_map(move |edge| {
         if trie.find_subset(instance.edge(edge)) {
@@ -108,8 +122,29 @@ fn find_dominated_edges(instance: &Instance) -> impl Iterator<Item = ReducedItem
     })
 }
 
+/// Finds another alive edge whose nodes are a subset of those of the given
+/// edge, which makes the given edge removable by edge domination.
+pub fn find_dominating_edge(instance: &Instance, edge: EdgeIdx) -> Option<EdgeIdx> {
+    let mut in_edge = vec![false; instance.num_nodes_total()];
+    for node in instance.edge(edge) {
+        in_edge[node.idx()] = true;
+    }
+    instance
+        .edge(edge)
+        .flat_map(|node| instance.node(node))
+        .find(|&other_edge| {
+            other_edge != edge
+                && instance.edge_size(other_edge) <= instance.edge_size(edge)
+                && instance.edge(other_edge).all(|node| in_edge[node.idx()])
+        })
+}
+
+/// Finds the nodes of edges of size one, which every hitting set contains.
+///
+/// The nodes are returned by increasing index, so that they are forced in
+/// the same order regardless of the internal order of the edges.
 fn find_forced_nodes(instance: &Instance) -> impl Iterator<Item = ReducedItem> {
-    let forced: IdxHashSet<_> = instance
+    let mut forced: Vec<_> = instance
         .edges()
         .iter()
         .copied()
@@ -124,9 +159,25 @@ fn find_forced_nodes(instance: &Instance) -> impl Iterator<Item = ReducedItem> {
             })
         })
         .collect();
+    forced.sort_unstable();
+    forced.dedup();
     forced.into_iter().map(ReducedItem::ForcedNode)
 }
 
+/// Finds a node contained in all remaining edges, which on its own is a
+/// minimum hitting set of the remaining instance.
+fn find_universal_node(instance: &Instance) -> Option<NodeIdx> {
+    if instance.max_node_degree() != Some(instance.num_edges()) {
+        return None;
+    }
+
+    instance
+        .nodes()
+        .iter()
+        .copied()
+        .find(|&node| instance.node_degree(node) == instance.num_edges())
+}
+
 fn find_costly_discards_using_efficiency_bound<'a>(
     instance: &'a Instance,
     lower_bound_breakpoint: usize,
@@ -172,6 +223,7 @@ fn find_costly_discard_using_packing_from_scratch(
 
     let mut nodes = instance.nodes().to_vec();
     nodes.sort_unstable_by_key(|&node| Reverse(instance.node_degree(node)));
+    let mut degree_buffer = Vec::new();
     nodes
         .into_iter()
         .take(settings.packing_from_scratch_limit)
@@ -180,7 +232,7 @@ fn find_costly_discard_using_packing_from_scratch(
             instance.delete_node(node);
             let packing_bound = PackingBound::new(instance, settings);
             let new_lower_bound = if settings.enable_sum_over_packing_bound {
-                packing_bound.calc_sum_over_packing_bound(instance)
+                packing_bound.calc_sum_over_packing_bound_in(&mut degree_buffer, instance)
             } else {
                 packing_bound.bound()
             };
@@ -194,7 +246,199 @@ fn find_costly_discard_using_packing_from_scratch(
         })
 }
 
-pub fn calc_greedy_approximation(instance: &Instance) -> Vec<NodeIdx> {
+/// Probes the nodes of highest degree by tentatively including them in the
+/// hitting set. If the lower bound of the remaining instance shows that
+/// including a node cannot lead to a smaller hitting set, it can be removed.
+fn find_failed_inclusion_using_probing(
+    instance: &mut Instance,
+    lower_bound_breakpoint: usize,
+    settings: &Settings,
+) -> Option<ReducedItem> {
+    if settings.probing_limit == 0 {
+        return None;
+    }
+
+    let mut nodes = instance.nodes().to_vec();
+    nodes.sort_unstable_by_key(|&node| Reverse(instance.node_degree(node)));
+    let mut degree_buffer = Vec::new();
+    nodes
+        .into_iter()
+        .take(settings.probing_limit)
+        .find_map(|node| {
+            instance.delete_node(node);
+            instance.delete_incident_edges(node);
+            let packing_bound = PackingBound::new(instance, settings);
+            let new_lower_bound = if settings.enable_sum_over_packing_bound {
+                packing_bound.calc_sum_over_packing_bound_in(&mut degree_buffer, instance)
+            } else {
+                packing_bound.bound()
+            };
+            instance.restore_incident_edges(node);
+            instance.restore_node(node);
+
+            if new_lower_bound + 1 >= lower_bound_breakpoint {
+                Some(ReducedItem::RemovedNode(node))
+            } else {
+                None
+            }
+        })
+}
+
+pub fn calc_greedy_approximation(instance: &Instance, strategy: GreedyStrategy) -> Vec<NodeIdx> {
+    match strategy {
+        GreedyStrategy::Degree => calc_degree_greedy_approximation(instance),
+        GreedyStrategy::Density => calc_density_greedy_approximation(instance),
+        GreedyStrategy::PrimalDual => calc_primal_dual_approximation(instance),
+    }
+}
+
+/// Runs every greedy strategy, removes redundant nodes from each result and
+/// returns the smallest hitting set found.
+pub fn calc_strong_greedy_approximation(instance: &Instance) -> Vec<NodeIdx> {
+    [
+        GreedyStrategy::Degree,
+        GreedyStrategy::Density,
+        GreedyStrategy::PrimalDual,
+    ]
+    .into_iter()
+    .map(|strategy| {
+        let mut hs = calc_greedy_approximation(instance, strategy);
+        remove_redundant_nodes(instance, &mut hs);
+        hs
+    })
+    .min_by_key(Vec::len)
+    .expect("At least one greedy strategy")
+}
+
+/// Removes nodes from a hitting set whose edges are all hit by other nodes,
+/// later nodes first, until the hitting set is inclusion-minimal.
+fn remove_redundant_nodes(instance: &Instance, hs: &mut Vec<NodeIdx>) {
+    let mut hit_count = vec![0_usize; instance.num_edges_total()];
+    for &node in hs.iter() {
+        for edge in instance.node(node) {
+            hit_count[edge.idx()] += 1;
+        }
+    }
+
+    let mut redundant = vec![false; hs.len()];
+    for (idx, &node) in hs.iter().enumerate().rev() {
+        if instance.node(node).all(|edge| hit_count[edge.idx()] > 1) {
+            redundant[idx] = true;
+            for edge in instance.node(node) {
+                hit_count[edge.idx()] -= 1;
+            }
+        }
+    }
+
+    let mut idx = 0;
+    hs.retain(|_| {
+        idx += 1;
+        !redundant[idx - 1]
+    });
+}
+
+/// Nodes whose load in the fractional packing is this close to 1 count as
+/// saturated
+const SATURATION_TOLERANCE: f64 = 1e-9;
+
+/// Raising the weight of an edge in the fractional packing only stops once
+/// one of its nodes is saturated, so the saturated nodes hit every edge. Edges
+/// left unhit due to rounding errors get a node of maximum degree.
+fn calc_primal_dual_approximation(instance: &Instance) -> Vec<NodeIdx> {
+    let (_, load) = lower_bound::calc_fractional_packing(instance);
+    let mut hs: Vec<_> = instance
+        .nodes()
+        .iter()
+        .copied()
+        .filter(|node| load[node.idx()] >= 1.0 - SATURATION_TOLERANCE)
+        .collect();
+    let mut is_chosen = vec![false; instance.num_nodes_total()];
+    for &node in &hs {
+        is_chosen[node.idx()] = true;
+    }
+    for edge in instance.uncovered_edges(&hs) {
+        if instance.edge(edge).any(|node| is_chosen[node.idx()]) {
+            continue;
+        }
+        let node = instance
+            .edge(edge)
+            .max_by_key(|&node| instance.node_degree(node))
+            .expect("Empty edge in instance");
+        is_chosen[node.idx()] = true;
+        hs.push(node);
+    }
+    remove_redundant_nodes(instance, &mut hs);
+    hs
+}
+
+/// Priority of a node in the density greedy, ordered by `f64::total_cmp`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DensityScore(f64);
+
+impl Eq for DensityScore {}
+
+impl PartialOrd for DensityScore {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DensityScore {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn calc_density_greedy_approximation(instance: &Instance) -> Vec<NodeIdx> {
+    let mut hit = vec![true; instance.num_edges_total()];
+    for edge in instance.edges() {
+        hit[edge.idx()] = false;
+    }
+    let edge_weight = |edge| (instance.edge_size(edge) as f64).recip();
+    let mut node_scores = vec![0.0; instance.num_nodes_total()];
+    let mut node_queue = BinaryHeap::new();
+    for &node in instance.nodes() {
+        node_scores[node.idx()] = instance.node(node).map(edge_weight).sum();
+        node_queue.push((DensityScore(node_scores[node.idx()]), node));
+    }
+
+    let mut hs = Vec::new();
+    let mut num_hit = 0;
+    while let Some((DensityScore(score), node)) = node_queue.pop() {
+        if num_hit == instance.num_edges() {
+            break;
+        }
+        // Scores only decrease, so entries that differ from the current
+        // score are outdated
+        if score.to_bits() != node_scores[node.idx()].to_bits() {
+            continue;
+        }
+
+        hs.push(node);
+        node_scores[node.idx()] = f64::NAN; // Invalidates remaining entries
+        for edge in instance.node(node) {
+            if hit[edge.idx()] {
+                continue;
+            }
+
+            hit[edge.idx()] = true;
+            num_hit += 1;
+            let weight = edge_weight(edge);
+            for edge_node in instance.edge(edge) {
+                let edge_node_score = &mut node_scores[edge_node.idx()];
+                if !edge_node_score.is_nan() {
+                    *edge_node_score -= weight;
+                    node_queue.push((DensityScore(*edge_node_score), edge_node));
+                }
+            }
+        }
+    }
+
+    hs
+}
+
+fn calc_degree_greedy_approximation(instance: &Instance) -> Vec<NodeIdx> {
     let mut hit = vec![true; instance.num_edges_total()];
     for edge in instance.edges() {
         hit[edge.idx()] = false;
@@ -239,8 +483,9 @@ fn recalculate_greedy_upper_bound(instance: &Instance, state: &mut State, report
     report.reductions.greedy_runs += 1;
     let improvements_list_ref = &mut report.upper_bound_improvements;
     let branching_steps = report.branching_steps;
+    let greedy_strategy = report.settings.greedy_strategy;
     collect_time_info(&mut report.runtimes.greedy, || {
-        let greedy = calc_greedy_approximation(instance);
+        let greedy = calc_greedy_approximation(instance, greedy_strategy);
         if state.partial_hs.len() + greedy.len() < state.minimum_hs.len() {
             state.minimum_hs.clear();
             state.minimum_hs.extend(state.partial_hs.iter().copied());
@@ -260,6 +505,37 @@ fn recalculate_greedy_upper_bound(instance: &Instance, state: &mut State, report
     });
 }
 
+/// Tracks whether an expensive reduction has recently been useful, so that it
+/// can be skipped in between while it keeps finding nothing.
+#[derive(Debug, Clone, Default)]
+pub struct AdaptiveSkip {
+    interval: usize,
+    remaining: usize,
+}
+
+impl AdaptiveSkip {
+    /// Maximum number of consecutive skips before the reduction is run again
+    const MAX_INTERVAL: usize = 64;
+
+    fn should_run(&mut self) -> bool {
+        if self.remaining == 0 {
+            true
+        } else {
+            self.remaining -= 1;
+            false
+        }
+    }
+
+    fn record(&mut self, found_items: bool) {
+        self.interval = if found_items {
+            0
+        } else {
+            (self.interval * 2).clamp(1, Self::MAX_INTERVAL)
+        };
+        self.remaining = self.interval;
+    }
+}
+
 fn collect_time_info<T>(runtime: &mut Duration, func: impl FnOnce() -> T) -> T {
     let before = Instant::now();
     let result = func();
@@ -271,6 +547,7 @@ fn run_reduction<I>(
     reduced_items: &mut Vec<ReducedItem>,
     runtime: &mut Duration,
     runs: &mut usize,
+    successful_runs: &mut usize,
     item_counter: &mut usize,
     func: impl FnOnce() -> I,
 ) where
@@ -281,6 +558,9 @@ fn run_reduction<I>(
     collect_time_info(runtime, || {
         reduced_items.extend(func());
     });
+    if reduced_items.len() > len_before {
+        *successful_runs += 1;
+    }
     *item_counter += reduced_items.len() - len_before;
 }
 
@@ -292,7 +572,7 @@ pub fn reduce(
 ) -> (ReductionResult, Reduction) {
     if report.settings.greedy_mode == GreedyMode::Once {
         recalculate_greedy_upper_bound(instance, state, report);
-        if state.minimum_hs.len() <= report.settings.stop_at {
+        if state.minimum_hs.len() <= state.stop_at {
             return (ReductionResult::Stop, Reduction(vec![]));
         }
     }
@@ -310,7 +590,7 @@ pub fn reduce(
 
         if report.settings.greedy_mode == GreedyMode::AlwaysBeforeBounds {
             recalculate_greedy_upper_bound(instance, state, report);
-            if state.minimum_hs.len() <= report.settings.stop_at {
+            if state.minimum_hs.len() <= state.stop_at {
                 break ReductionResult::Stop;
             }
             if state.partial_hs.len() >= state.minimum_hs.len() {
@@ -357,6 +637,17 @@ pub fn reduce(
             Vec::new()
         };
 
+        if report.settings.enable_fractional_packing_bound {
+            let fractional_packing_bound =
+                collect_time_info(&mut report.runtimes.fractional_packing_bound, || {
+                    lower_bound::calc_fractional_packing_bound(instance)
+                });
+            if fractional_packing_bound.round().unwrap_or(usize::MAX) >= lower_bound_breakpoint {
+                report.reductions.fractional_packing_bound_breaks += 1;
+                break ReductionResult::Unsolvable;
+            }
+        }
+
         let packing_bound = if report.settings.enable_packing_bound {
             let settings_ref = &report.settings;
             let packing_bound = collect_time_info(&mut report.runtimes.packing_bound, || {
@@ -383,13 +674,19 @@ pub fn reduce(
         }
 
         let unchanged_len = reduced_items.len();
-        run_reduction(
-            &mut reduced_items,
-            &mut report.runtimes.forced_vertex,
-            &mut report.reductions.forced_vertex_runs,
-            &mut report.reductions.forced_vertices_found,
-            || find_forced_nodes(instance),
-        );
+        if let Some(node) = find_universal_node(instance) {
+            report.reductions.universal_vertices_found += 1;
+            reduced_items.push(ReducedItem::ForcedNode(node));
+        } else {
+            run_reduction(
+                &mut reduced_items,
+                &mut report.runtimes.forced_vertex,
+                &mut report.reductions.forced_vertex_runs,
+                &mut report.reductions.forced_vertex_successful_runs,
+                &mut report.reductions.forced_vertices_found,
+                || find_forced_nodes(instance),
+            );
+        }
 
         if reduced_items.len() == unchanged_len && report.settings.enable_efficiency_bound {
             // Do not time this step as all costly parts are integrated into the
@@ -400,6 +697,7 @@ pub fn reduce(
                 &mut reduced_items,
                 &mut dummy_duration,
                 &mut report.reductions.costly_discard_efficiency_runs,
+                &mut report.reductions.costly_discard_efficiency_successful_runs,
                 &mut report.reductions.costly_discard_efficiency_vertices_found,
                 || {
                     find_costly_discards_using_efficiency_bound(
@@ -416,6 +714,9 @@ pub fn reduce(
                 &mut reduced_items,
                 &mut report.runtimes.costly_discard_packing_update,
                 &mut report.reductions.costly_discard_packing_update_runs,
+                &mut report
+                    .reductions
+                    .costly_discard_packing_update_successful_runs,
                 &mut report
                     .reductions
                     .costly_discard_packing_update_vertices_found,
@@ -433,7 +734,7 @@ pub fn reduce(
             && report.settings.greedy_mode == GreedyMode::AlwaysBeforeExpensiveReductions
         {
             recalculate_greedy_upper_bound(instance, state, report);
-            if state.minimum_hs.len() <= report.settings.stop_at {
+            if state.minimum_hs.len() <= state.stop_at {
                 break ReductionResult::Stop;
             }
             if state.partial_hs.len() >= state.minimum_hs.len() {
@@ -452,6 +753,9 @@ pub fn reduce(
                 &mut reduced_items,
                 &mut report.runtimes.costly_discard_packing_from_scratch,
                 &mut report.reductions.costly_discard_packing_from_scratch_runs,
+                &mut report
+                    .reductions
+                    .costly_discard_packing_from_scratch_successful_runs,
                 &mut dummy_counter,
                 || {
                     let result = find_costly_discard_using_packing_from_scratch(
@@ -474,23 +778,60 @@ pub fn reduce(
         }
 
         if reduced_items.len() == unchanged_len {
+            let settings_ref = &report.settings;
             run_reduction(
                 &mut reduced_items,
-                &mut report.runtimes.vertex_domination,
-                &mut report.reductions.vertex_dominations_runs,
-                &mut report.reductions.vertex_dominations_vertices_found,
-                || find_dominated_nodes(instance),
+                &mut report.runtimes.probing,
+                &mut report.reductions.probing_runs,
+                &mut report.reductions.probing_successful_runs,
+                &mut report.reductions.probing_vertices_found,
+                || {
+                    find_failed_inclusion_using_probing(
+                        instance,
+                        lower_bound_breakpoint,
+                        settings_ref,
+                    )
+                },
             );
         }
 
+        // Skipping domination only leads to branching earlier, so the adaptive
+        // mode never affects the result
+        let adaptive_domination = report.settings.adaptive_domination;
         if reduced_items.len() == unchanged_len {
-            run_reduction(
-                &mut reduced_items,
-                &mut report.runtimes.edge_domination,
-                &mut report.reductions.edge_dominations_runs,
-                &mut report.reductions.edge_dominations_edges_found,
-                || find_dominated_edges(instance),
-            );
+            if !adaptive_domination || state.vertex_domination_skip.should_run() {
+                run_reduction(
+                    &mut reduced_items,
+                    &mut report.runtimes.vertex_domination,
+                    &mut report.reductions.vertex_dominations_runs,
+                    &mut report.reductions.vertex_dominations_successful_runs,
+                    &mut report.reductions.vertex_dominations_vertices_found,
+                    || find_dominated_nodes(instance),
+                );
+                state
+                    .vertex_domination_skip
+                    .record(reduced_items.len() > unchanged_len);
+            } else {
+                report.reductions.vertex_dominations_skipped += 1;
+            }
+        }
+
+        if reduced_items.len() == unchanged_len {
+            if !adaptive_domination || state.edge_domination_skip.should_run() {
+                run_reduction(
+                    &mut reduced_items,
+                    &mut report.runtimes.edge_domination,
+                    &mut report.reductions.edge_dominations_runs,
+                    &mut report.reductions.edge_dominations_successful_runs,
+                    &mut report.reductions.edge_dominations_edges_found,
+                    || find_dominated_edges(instance),
+                );
+                state
+                    .edge_domination_skip
+                    .record(reduced_items.len() > unchanged_len);
+            } else {
+                report.reductions.edge_dominations_skipped += 1;
+            }
         }
 
         if reduced_items.len() == unchanged_len {
@@ -536,3 +877,72 @@ pub fn reduce_for_ilp(instance: &mut Instance) -> (usize, usize) {
 
     (reduced_nodes, reduced_edges)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::LoadOptions;
+
+    #[test]
+    fn forced_nodes_by_increasing_index() {
+        let text = "5 5\n1 3\n2 0 1\n1 1\n1 3\n1 0\n";
+        let mut instance =
+            Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        // Reorder the alive edges
+        instance.delete_edge(EdgeIdx::from(0_usize));
+        instance.restore_edge(EdgeIdx::from(0_usize));
+        let forced: Vec<_> = find_forced_nodes(&instance)
+            .map(|item| match item {
+                ReducedItem::ForcedNode(node) => node.idx(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(forced, [0, 1, 3]);
+    }
+
+    #[test]
+    fn primal_dual_beats_degree_greedy() {
+        // Degree greedy needs three nodes, while the nodes saturated by the
+        // fractional packing are the minimum hitting set {2, 5}
+        let text = "6 5\n2 2 4\n3 1 2 4\n2 0 5\n3 1 4 5\n2 2 3\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let greedy = calc_greedy_approximation(&instance, GreedyStrategy::Degree);
+        let mut primal_dual = calc_greedy_approximation(&instance, GreedyStrategy::PrimalDual);
+        assert!(instance.uncovered_edges(&primal_dual).is_empty());
+        primal_dual.sort_unstable();
+        assert_eq!(
+            primal_dual,
+            [NodeIdx::from(2_usize), NodeIdx::from(5_usize)]
+        );
+        assert_eq!(greedy.len(), 3);
+    }
+
+    #[test]
+    fn successful_runs_only_count_runs_that_reduce() {
+        let mut reduced_items = Vec::new();
+        let mut runtime = Duration::default();
+        let (mut runs, mut successful_runs, mut num_found) = (0, 0, 0);
+        for num_forced in [2_usize, 0, 1, 0] {
+            run_reduction(
+                &mut reduced_items,
+                &mut runtime,
+                &mut runs,
+                &mut successful_runs,
+                &mut num_found,
+                || (0..num_forced).map(|node| ReducedItem::ForcedNode(NodeIdx::from(node))),
+            );
+        }
+        assert_eq!((runs, successful_runs, num_found), (4, 2, 3));
+        assert_eq!(reduced_items.len(), 3);
+    }
+
+    #[test]
+    fn dominating_edge_explains_removable_edges() {
+        let text = "4 3\n2 0 1\n3 0 1 2\n2 2 3\n";
+        let instance = Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap();
+        let edge = |idx: usize| EdgeIdx::from(idx);
+        assert_eq!(find_dominating_edge(&instance, edge(1)), Some(edge(0)));
+        assert_eq!(find_dominating_edge(&instance, edge(0)), None);
+        assert_eq!(find_dominating_edge(&instance, edge(2)), None);
+    }
+}
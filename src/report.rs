@@ -1,6 +1,7 @@
 use crate::instance::NodeIdx;
+use log::warn;
 use serde::{Deserialize, Serialize, Serializer};
-use std::time::Duration;
+use std::{borrow::Cow, collections::HashMap, time::Duration};
 
 fn serialize_duration_as_seconds<S>(duration: &Duration, ser: S) -> Result<S::Ok, S::Error>
 where
@@ -17,6 +18,20 @@ pub struct RuntimeStats {
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub greedy: Duration,
 
+    /// Time spent on the greedy approximation at the root, before solving
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub root_greedy: Duration,
+
+    /// Time after which the first smaller hitting set than the initial one
+    /// was found, or zero if there was none
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub until_first_improvement: Duration,
+
+    /// Time after which the final hitting set was found, or zero if it is
+    /// the initial one. The remaining time was spent proving its optimality.
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub until_final_improvement: Duration,
+
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub max_degree_bound: Duration,
 
@@ -26,6 +41,9 @@ pub struct RuntimeStats {
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub efficiency_bound: Duration,
 
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub fractional_packing_bound: Duration,
+
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub packing_bound: Duration,
 
@@ -41,6 +59,9 @@ pub struct RuntimeStats {
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub costly_discard_packing_from_scratch: Duration,
 
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub probing: Duration,
+
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub vertex_domination: Duration,
 
@@ -49,6 +70,36 @@ pub struct RuntimeStats {
 
     #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub applying_reductions: Duration,
+
+    /// Part of the total time not spent in any of the phases above, mostly
+    /// branching and restoring the instance
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub branching: Duration,
+}
+
+impl RuntimeStats {
+    /// Sum of the times measured for the individual phases of the search
+    fn measured_phases(&self) -> Duration {
+        self.greedy
+            + self.max_degree_bound
+            + self.sum_degree_bound
+            + self.efficiency_bound
+            + self.fractional_packing_bound
+            + self.packing_bound
+            + self.sum_over_packing_bound
+            + self.forced_vertex
+            + self.costly_discard_packing_update
+            + self.costly_discard_packing_from_scratch
+            + self.probing
+            + self.vertex_domination
+            + self.edge_domination
+            + self.applying_reductions
+    }
+
+    /// Attributes the time not covered by any phase to branching
+    pub fn set_branching_remainder(&mut self) {
+        self.branching = self.total.saturating_sub(self.measured_phases());
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -60,27 +111,97 @@ pub struct UpperBoundImprovement {
     pub runtime: Duration,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchingSample {
+    pub branching_steps: usize,
+
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub runtime: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentReport {
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub opt: usize,
+    pub proven_optimal: bool,
+    pub branching_steps: usize,
+
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub runtime: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SearchTreeOutcome {
+    /// The reductions turned the partial hitting set into a hitting set
+    Solved,
+
+    /// A lower bound showed that no smaller hitting set can be found here
+    Pruned,
+
+    /// No reduction applied, so the solver branched further
+    Branched,
+
+    /// The search stopped before this node was fully explored
+    Stopped,
+}
+
+/// Node of the exported branch-and-bound tree
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchTreeNode {
+    /// Node of the instance included or discarded on the way here, missing
+    /// for the root
+    pub node: Option<NodeIdx>,
+    pub included: bool,
+
+    /// Size of the partial hitting set when entering this search node
+    pub partial_size: usize,
+
+    /// Size of the best known hitting set when entering this search node
+    pub best_known: usize,
+
+    pub outcome: SearchTreeOutcome,
+
+    /// Whether children below the maximum depth were left out
+    pub truncated: bool,
+
+    pub children: Vec<SearchTreeNode>,
+}
+
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct ReductionStats {
     pub max_degree_bound_breaks: usize,
     pub sum_degree_bound_breaks: usize,
     pub efficiency_degree_bound_breaks: usize,
+    pub fractional_packing_bound_breaks: usize,
     pub packing_bound_breaks: usize,
     pub sum_over_packing_bound_breaks: usize,
 
     pub greedy_runs: usize,
     pub forced_vertex_runs: usize,
+    pub forced_vertex_successful_runs: usize,
     pub forced_vertices_found: usize,
+    pub universal_vertices_found: usize,
     pub costly_discard_efficiency_runs: usize,
+    pub costly_discard_efficiency_successful_runs: usize,
     pub costly_discard_efficiency_vertices_found: usize,
     pub costly_discard_packing_update_runs: usize,
+    pub costly_discard_packing_update_successful_runs: usize,
     pub costly_discard_packing_update_vertices_found: usize,
     pub costly_discard_packing_from_scratch_runs: usize,
+    pub costly_discard_packing_from_scratch_successful_runs: usize,
     pub costly_discard_packing_from_scratch_steps_per_run: Vec<usize>,
+    pub probing_runs: usize,
+    pub probing_successful_runs: usize,
+    pub probing_vertices_found: usize,
     pub vertex_dominations_runs: usize,
+    pub vertex_dominations_successful_runs: usize,
     pub vertex_dominations_vertices_found: usize,
+    pub vertex_dominations_skipped: usize,
     pub edge_dominations_runs: usize,
+    pub edge_dominations_successful_runs: usize,
     pub edge_dominations_edges_found: usize,
+    pub edge_dominations_skipped: usize,
 }
 
 impl ReductionStats {
@@ -100,11 +221,57 @@ pub struct RootBounds {
     pub max_degree: usize,
     pub sum_degree: usize,
     pub efficiency: usize,
+    pub fractional_packing: usize,
     pub packing: usize,
     pub sum_over_packing: usize,
     pub greedy_upper: usize,
 }
 
+impl RootBounds {
+    /// The strongest of the lower bounds.
+    pub fn lower_bound(&self) -> usize {
+        self.max_degree
+            .max(self.sum_degree)
+            .max(self.efficiency)
+            .max(self.fractional_packing)
+            .max(self.packing)
+            .max(self.sum_over_packing)
+    }
+
+    /// Difference between the greedy upper bound and the strongest lower
+    /// bound.
+    pub fn initial_gap(&self) -> usize {
+        self.greedy_upper.saturating_sub(self.lower_bound())
+    }
+
+    /// Rough guess of how hard the instance is, based on the initial gap
+    /// relative to the lower bound.
+    pub fn difficulty(&self) -> &'static str {
+        let gap = self.initial_gap();
+        if gap == 0 {
+            "trivial"
+        } else if gap * 10 <= self.lower_bound() {
+            "easy"
+        } else if gap * 2 <= self.lower_bound() {
+            "moderate"
+        } else {
+            "hard"
+        }
+    }
+
+    /// Adds the bounds of another component, since the bounds of disjoint
+    /// components add up.
+    pub fn add_component(&mut self, other: &RootBounds) {
+        self.max_degree += other.max_degree;
+        self.sum_degree += other.sum_degree;
+        self.efficiency += other.efficiency;
+        self.fractional_packing += other.fractional_packing;
+        self.packing += other.packing;
+        self.sum_over_packing += other.sum_over_packing;
+        self.greedy_upper += other.greedy_upper;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GreedyMode {
     Never,
@@ -113,6 +280,35 @@ pub enum GreedyMode {
     AlwaysBeforeExpensiveReductions,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GreedyStrategy {
+    /// Pick the node hitting the most edges that are not hit yet
+    #[default]
+    Degree,
+
+    /// Pick the node maximizing the sum of `1 / edge size` over the edges
+    /// it hits that are not hit yet, preferring nodes in small edges
+    Density,
+
+    /// Pick all nodes saturated by the fractional edge packing lower bound,
+    /// then drop redundant ones. This rounds the dual of the LP relaxation.
+    PrimalDual,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BranchingMode {
+    /// Branch on including or discarding a node of maximum degree
+    #[default]
+    Node,
+
+    /// Branch on which node hits a smallest remaining edge
+    Edge,
+
+    /// Branch on including or discarding a node whose degree is closest to
+    /// half the number of remaining edges, splitting them most evenly
+    Balanced,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Settings {
@@ -128,6 +324,10 @@ pub struct Settings {
     /// Enable the efficiency bound (including costly discards)
     pub enable_efficiency_bound: bool,
 
+    /// Enable the fractional packing bound
+    #[serde(default)]
+    pub enable_fractional_packing_bound: bool,
+
     /// Enable the packing bound (including costly discards)
     pub enable_packing_bound: bool,
 
@@ -137,29 +337,266 @@ pub struct Settings {
     /// Number of nodes to check in the costly discard with from-scratch packing step
     pub packing_from_scratch_limit: usize,
 
+    /// Number of nodes to probe by tentatively including them, removing them
+    /// if this cannot lead to a smaller hitting set (0 disables probing)
+    #[serde(default)]
+    pub probing_limit: usize,
+
     /// When to update the greedy upper bound during reductions
     pub greedy_mode: GreedyMode,
 
+    /// Break ties between branching candidates and between edges of the
+    /// packing bound by picking the lowest index, making the search
+    /// independent of the internal order of nodes and edges
+    #[serde(default)]
+    pub lowest_index_tie_break: bool,
+
+    /// Seconds between progress log messages during the search, defaults to
+    /// one minute
+    #[serde(default)]
+    pub progress_interval_secs: Option<u64>,
+
+    /// Solve each connected component of the instance separately
+    #[serde(default)]
+    pub decompose_components: bool,
+
+    /// Reduce the instance once and solve the remaining kernel as a new
+    /// instance without the deleted nodes and edges
+    #[serde(default)]
+    pub compact_kernel: bool,
+
+    /// First solve only this many of the smallest edges and stop if their
+    /// minimum hitting set already hits all edges
+    #[serde(default)]
+    pub core_size: Option<usize>,
+
+    /// How the greedy approximation picks nodes
+    #[serde(default)]
+    pub greedy_strategy: GreedyStrategy,
+
+    /// At the root, run every greedy strategy, drop redundant nodes and keep
+    /// the smallest result
+    #[serde(default)]
+    pub strong_root_greedy: bool,
+
+    /// Skip the domination rules for a while after they found nothing
+    #[serde(default)]
+    pub adaptive_domination: bool,
+
+    /// Estimate the treewidth of the instance before solving
+    #[serde(default)]
+    pub estimate_treewidth: bool,
+
+    /// Count the pairs of disjoint edges of the instance before solving
+    #[serde(default)]
+    pub count_disjoint_edge_pairs: bool,
+
+    /// Count the nodes of the instance before solving that only occur in
+    /// edges with more nodes than this
+    #[serde(default)]
+    pub large_edge_threshold: Option<usize>,
+
+    /// Record the runtime whenever the number of branching steps reaches a
+    /// power of two
+    #[serde(default)]
+    pub sample_branching_times: bool,
+
+    /// Record the explored search tree up to this depth for export
+    #[serde(default)]
+    pub search_tree_depth: Option<usize>,
+
+    /// How to branch once no further reductions apply
+    #[serde(default)]
+    pub branching_mode: BranchingMode,
+
     /// Hitting set to initialize the solver with
-    pub initial_hitting_set: Option<Vec<NodeIdx>>,
+    pub initial_hitting_set: Option<Vec<u32>>,
+
+    /// Fix an invalid initial hitting set instead of rejecting it
+    #[serde(default)]
+    pub repair_initial_hitting_set: bool,
+
+    /// Without an initial hitting set, start from one taking a node of each
+    /// edge not hit yet instead of all nodes. With `GreedyMode::Never`, this
+    /// also replaces the root upper bound.
+    #[serde(default)]
+    pub trivial_initial_hitting_set: bool,
 
     /// Stop solving once a hitting set this size or smaller is found
     #[serde(default)]
     pub stop_at: usize,
+
+    /// Stop solving after this many restore operations during backtracking
+    pub max_restore_operations: Option<usize>,
+
+    /// Stop solving once the estimated memory used by the search exceeds
+    /// this many bytes
+    pub memory_limit: Option<usize>,
+
+    /// Stop solving once a hitting set within this factor of the minimum is
+    /// guaranteed, based on the lower bound at the root
+    pub approximation_ratio: Option<f64>,
+
+    /// Stop solving once the found hitting set is guaranteed to have at most
+    /// this many more nodes than a minimum one, based on the lower bound at
+    /// the root
+    pub max_gap: Option<usize>,
+
+    /// Warn at the start of solving if the greedy hitting set has at least
+    /// this many more nodes than the root lower bound
+    #[serde(default)]
+    pub warn_initial_gap: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Report {
     pub file_name: String,
+    pub metadata: HashMap<String, String>,
     pub opt: usize,
     pub branching_steps: usize,
+
+    /// Branching steps after which the final hitting set was found, or zero
+    /// if it is the initial one. The remaining steps only proved optimality.
+    pub branching_steps_until_final_improvement: usize,
+
+    /// Branches adding a node to the hitting set. Edge branching creates
+    /// one such branch per node it tries. Nodes forced by edges with a
+    /// single node are no branches, `reductions.forced_vertices_found`
+    /// counts them instead.
+    pub include_branches: usize,
+
+    /// Branches removing a node without adding it to the hitting set. Only
+    /// node branching creates these.
+    pub discard_branches: usize,
+
+    /// Largest number of branching decisions on a path of the search tree
+    pub max_search_depth: usize,
+
+    /// Branching factor a uniform search tree of depth `max_search_depth`
+    /// would need to contain as many search nodes as the explored one
+    pub effective_branching_factor: f64,
+
+    pub restore_operations: usize,
+
+    /// Progress messages logged during the search, see
+    /// `progress_interval_secs`
+    pub progress_messages: usize,
+
+    pub certified_ratio: f64,
+    pub proven_optimal: bool,
+    pub unique_minimum: Option<bool>,
+    pub treewidth_upper_bound: Option<usize>,
+    pub disjoint_edge_pairs: Option<usize>,
+    pub nodes_only_in_large_edges: Option<usize>,
+
+    /// Incidence entries visited while solving, only counted when built
+    /// with the `count-traversals` feature
+    pub incidence_traversals: Option<usize>,
+
+    /// Size of the instance after applying the reductions at the root, if
+    /// the search was started
+    pub kernel_nodes: Option<usize>,
+    pub kernel_edges: Option<usize>,
+
+    /// Fraction of edges remaining after applying the reductions at the root
+    pub kernel_ratio: Option<f64>,
+
     pub upper_bound_improvements: Vec<UpperBoundImprovement>,
+    pub branching_samples: Vec<BranchingSample>,
+
+    /// Results for each connected component, if they were solved separately
+    pub components: Vec<ComponentReport>,
+
+    /// Written to a separate file since it can be large
+    #[serde(skip)]
+    pub search_tree: Option<SearchTreeNode>,
+
     pub settings: Settings,
     pub root_bounds: RootBounds,
     pub runtimes: RuntimeStats,
     pub reductions: ReductionStats,
 }
 
+impl Report {
+    /// Derives the effective branching factor from the branch counters and
+    /// `max_search_depth`.
+    pub fn set_effective_branching_factor(&mut self) {
+        let search_nodes = 1 + self.include_branches + self.discard_branches;
+        self.effective_branching_factor = if self.max_search_depth == 0 {
+            1.0
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let search_nodes = search_nodes as f64;
+            #[allow(clippy::cast_precision_loss)]
+            let depth = self.max_search_depth as f64;
+            search_nodes.powf(depth.recip())
+        };
+    }
+
+    /// Column names of the rows produced by `to_csv_row` for new files.
+    pub fn csv_header() -> String {
+        CSV_COLUMNS.join(",")
+    }
+
+    /// Summarizes the report as a single CSV row for aggregating benchmarks.
+    ///
+    /// Only the columns of `header` are written, so that rows can still be
+    /// appended to files started by older versions with fewer columns.
+    /// Columns that are not known are left empty.
+    pub fn to_csv_row(&self, header: &str) -> String {
+        let values = [
+            csv_field(&self.file_name).into_owned(),
+            self.opt.to_string(),
+            self.root_bounds.greedy_upper.to_string(),
+            self.runtimes.total.as_secs_f64().to_string(),
+            self.branching_steps.to_string(),
+            self.kernel_ratio
+                .map_or_else(String::new, |ratio| ratio.to_string()),
+        ];
+        select_csv_columns(&values, header)
+    }
+}
+
+const CSV_COLUMNS: [&str; 6] = [
+    "file_name",
+    "opt",
+    "greedy_upper",
+    "runtime",
+    "branching_steps",
+    "kernel_ratio",
+];
+
+/// Picks the values of the columns named in `header` from `values`, which
+/// are given in the order of `CSV_COLUMNS`. Unknown columns stay empty.
+fn select_csv_columns(values: &[String], header: &str) -> String {
+    let row: Vec<_> = header
+        .split(',')
+        .map(|column| {
+            CSV_COLUMNS
+                .iter()
+                .position(|&known| known == column)
+                .map_or_else(
+                    || {
+                        warn!("Leaving unknown csv column {:?} empty", column);
+                        ""
+                    },
+                    |idx| values[idx].as_str(),
+                )
+        })
+        .collect();
+    row.join(",")
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break, as
+/// described in RFC 4180.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[allow(clippy::module_name_repetitions)]
 pub struct IlpReductionReport {
@@ -169,3 +606,79 @@ pub struct IlpReductionReport {
     pub reduced_vertices: usize,
     pub reduced_edges: usize,
 }
+
+/// Runtime statistics over repeated solver runs on the same instance.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchStats {
+    pub opt: usize,
+    pub runs: usize,
+    pub branching_steps: usize,
+
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub min_runtime: Duration,
+
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub median_runtime: Duration,
+
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
+    pub max_runtime: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_special_characters() {
+        assert_eq!(csv_field("plain.hgr"), "plain.hgr");
+        assert_eq!(csv_field("a,b.hgr"), "\"a,b.hgr\"");
+        assert_eq!(csv_field("say \"hi\".hgr"), "\"say \"\"hi\"\".hgr\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn csv_columns_follow_existing_header() {
+        let values: Vec<_> = ["a.hgr", "3", "4", "0.5", "7", "0.25"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            select_csv_columns(&values, &Report::csv_header()),
+            "a.hgr,3,4,0.5,7,0.25"
+        );
+        // Header of files written before the kernel ratio was added
+        assert_eq!(
+            select_csv_columns(
+                &values,
+                "file_name,opt,greedy_upper,runtime,branching_steps"
+            ),
+            "a.hgr,3,4,0.5,7"
+        );
+        assert_eq!(
+            select_csv_columns(&values, "file_name,notes,opt"),
+            "a.hgr,,3"
+        );
+    }
+
+    #[test]
+    fn branching_time_is_the_unmeasured_remainder() {
+        let mut runtimes = RuntimeStats {
+            total: Duration::from_millis(100),
+            greedy: Duration::from_millis(10),
+            packing_bound: Duration::from_millis(30),
+            applying_reductions: Duration::from_millis(20),
+            ..RuntimeStats::default()
+        };
+        runtimes.set_branching_remainder();
+        assert_eq!(runtimes.branching, Duration::from_millis(40));
+        assert_eq!(
+            runtimes.measured_phases() + runtimes.branching,
+            runtimes.total
+        );
+
+        // Phases adding up to more than the total leave no branching time
+        runtimes.total = Duration::from_millis(50);
+        runtimes.set_branching_remainder();
+        assert_eq!(runtimes.branching, Duration::ZERO);
+    }
+}
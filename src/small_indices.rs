@@ -24,11 +24,31 @@ pub trait SmallIdx:
 
     fn idx(&self) -> usize;
 
-    #[allow(dead_code)]
     fn valid(&self) -> bool {
         *self != Self::INVALID
     }
 
+    /// Adds an offset to the index. Returns `None` if the result does not
+    /// fit or would be the `INVALID` sentinel.
+    fn checked_add(self, offset: usize) -> Option<Self> {
+        if !self.valid() {
+            return None;
+        }
+        let idx = u32::try_from(self.idx().checked_add(offset)?).ok()?;
+        let idx = <Self as From<u32>>::from(idx);
+        idx.valid().then_some(idx)
+    }
+
+    /// Subtracts an offset from the index. Returns `None` if the result
+    /// would be negative or the index is the `INVALID` sentinel.
+    fn checked_sub(self, offset: usize) -> Option<Self> {
+        if !self.valid() {
+            return None;
+        }
+        let idx = self.idx().checked_sub(offset)?;
+        Some(<Self as From<usize>>::from(idx))
+    }
+
     #[allow(dead_code)]
     fn idx_if_valid(&self) -> Option<usize> {
         if self.valid() {
@@ -113,3 +133,35 @@ pub type IdxHashMap<I, V> = FxHashMap<I, V>;
 
 /// Hash set with optimized hash function for small indices.
 pub type IdxHashSet<I> = FxHashSet<I>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    create_idx_struct!(TestIdx);
+
+    #[test]
+    fn checked_offsets() {
+        let idx = TestIdx::from(5_u32);
+        assert_eq!(idx.checked_add(3), Some(TestIdx::from(8_u32)));
+        assert_eq!(idx.checked_sub(5), Some(TestIdx::from(0_u32)));
+    }
+
+    #[test]
+    fn checked_offsets_overflow() {
+        let last = TestIdx::from(u32::MAX - 1);
+        assert_eq!(last.checked_add(0), Some(last));
+        // Would be the sentinel
+        assert_eq!(last.checked_add(1), None);
+        assert_eq!(last.checked_add(2), None);
+        assert_eq!(TestIdx::from(0_u32).checked_add(usize::MAX), None);
+        assert_eq!(TestIdx::INVALID.checked_add(0), None);
+    }
+
+    #[test]
+    fn checked_offsets_underflow() {
+        assert_eq!(TestIdx::from(0_u32).checked_sub(1), None);
+        assert_eq!(TestIdx::from(3_u32).checked_sub(4), None);
+        assert_eq!(TestIdx::INVALID.checked_sub(1), None);
+    }
+}
@@ -1,19 +1,27 @@
 use std::sync::Arc;
 use crate::{
-    instance::{Instance, NodeIdx},
+    instance::{incidence_traversals, EdgeIdx, Instance, NodeIdx},
     lower_bound::{self, PackingBound},
-    reductions::{self, ReductionResult},
-    report::{ReductionStats, Report, RootBounds, RuntimeStats, Settings, UpperBoundImprovement},
-    small_indices::{IdxHashSet, SmallIdx},
+    reductions::{self, AdaptiveSkip, ReductionResult},
+    report::{
+        BenchStats, BranchingMode, BranchingSample, ComponentReport, GreedyMode, ReductionStats,
+        Report, RootBounds, RuntimeStats, SearchTreeNode, SearchTreeOutcome, Settings,
+        UpperBoundImprovement,
+    },
+    small_indices::SmallIdx,
 };
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Context, Result};
 use log::{debug, info, trace, warn};
 use std::time::Instant;
 use signal_hook::{self, consts::SIGUSR1};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::cmp::Reverse;
 
 const ITERATION_LOG_INTERVAL_SECS: u64 = 60;
 
+/// Rough number of bytes used per recursion level, apart from the reduction
+const SEARCH_FRAME_BYTES: usize = 512;
+
 #[derive(Debug, Clone)]
 pub struct State {
     pub partial_hs: Vec<NodeIdx>,
@@ -21,6 +29,103 @@ pub struct State {
     pub solve_start_time: Instant,
     pub last_log_time: Instant,
     pub term: Arc<AtomicBool>,
+
+    /// Stop once a hitting set of this size or smaller is found
+    pub stop_at: usize,
+
+    pub vertex_domination_skip: AdaptiveSkip,
+    pub edge_domination_skip: AdaptiveSkip,
+
+    /// Coarse estimate of the memory used by the recursion in bytes
+    pub search_memory: usize,
+
+    /// Number of search nodes from the root to the current one, inclusive
+    pub search_path_len: usize,
+
+    pub search_tree: Option<SearchTreeRecorder>,
+}
+
+/// Builds the explored search tree for export, leaving out search nodes
+/// below a maximum depth.
+#[derive(Debug, Clone)]
+pub struct SearchTreeRecorder {
+    max_depth: usize,
+
+    /// Recorded search nodes on the path to the current one, root first
+    path: Vec<SearchTreeNode>,
+
+    /// Depth of the current search node, which can exceed the recorded path
+    depth: usize,
+
+    root: Option<SearchTreeNode>,
+}
+
+impl SearchTreeRecorder {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            path: Vec::new(),
+            depth: 0,
+            root: None,
+        }
+    }
+
+    fn enter(&mut self, decision: Option<(NodeIdx, bool)>, partial_size: usize, best_known: usize) {
+        if self.depth <= self.max_depth {
+            self.path.push(SearchTreeNode {
+                node: decision.map(|(node, _)| node),
+                included: decision.is_some_and(|(_, included)| included),
+                partial_size,
+                best_known,
+                outcome: SearchTreeOutcome::Stopped,
+                truncated: false,
+                children: Vec::new(),
+            });
+        } else if let Some(deepest) = self.path.last_mut() {
+            deepest.truncated = true;
+        }
+        self.depth += 1;
+    }
+
+    fn leave(&mut self) {
+        if self.depth == self.path.len() {
+            let search_node = self.path.pop().expect("Leaving the root twice");
+            match self.path.last_mut() {
+                Some(parent) => parent.children.push(search_node),
+                None => self.root = Some(search_node),
+            }
+        }
+        self.depth -= 1;
+    }
+
+    fn set_outcome(&mut self, outcome: SearchTreeOutcome) {
+        if self.depth == self.path.len() {
+            if let Some(current) = self.path.last_mut() {
+                current.outcome = outcome;
+            }
+        }
+    }
+}
+
+fn enter_search_node(state: &mut State, decision: Option<(NodeIdx, bool)>) {
+    state.search_path_len += 1;
+    let (partial_size, best_known) = (state.partial_hs.len(), state.minimum_hs.len());
+    if let Some(tree) = &mut state.search_tree {
+        tree.enter(decision, partial_size, best_known);
+    }
+}
+
+fn leave_search_node(state: &mut State) {
+    state.search_path_len -= 1;
+    if let Some(tree) = &mut state.search_tree {
+        tree.leave();
+    }
+}
+
+fn record_search_outcome(state: &mut State, outcome: SearchTreeOutcome) {
+    if let Some(tree) = &mut state.search_tree {
+        tree.set_outcome(outcome);
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -32,6 +137,16 @@ enum Status {
     Stop,
 }
 
+fn count_branching_step(state: &State, report: &mut Report) {
+    report.branching_steps += 1;
+    if report.settings.sample_branching_times && report.branching_steps.is_power_of_two() {
+        report.branching_samples.push(BranchingSample {
+            branching_steps: report.branching_steps,
+            runtime: state.solve_start_time.elapsed(),
+        });
+    }
+}
+
 fn branch_on(
     node: NodeIdx,
     instance: &mut Instance,
@@ -39,34 +154,146 @@ fn branch_on(
     report: &mut Report,
 ) -> Status {
     trace!("Branching on {}", node);
-    report.branching_steps += 1;
+    count_branching_step(state, report);
+    record_search_outcome(state, SearchTreeOutcome::Branched);
     instance.delete_node(node);
 
     instance.delete_incident_edges(node);
     state.partial_hs.push(node);
+    report.include_branches += 1;
+    enter_search_node(state, Some((node, true)));
     let status_without = solve_recursive(instance, state, report);
+    leave_search_node(state);
     debug_assert_eq!(state.partial_hs.last().copied(), Some(node));
     state.partial_hs.pop();
     instance.restore_incident_edges(node);
+    report.restore_operations += 1;
 
     if status_without == Status::Stop {
         instance.restore_node(node);
+        report.restore_operations += 1;
         return Status::Stop;
     }
 
+    report.discard_branches += 1;
+    enter_search_node(state, Some((node, false)));
     let status_with = solve_recursive(instance, state, report);
+    leave_search_node(state);
     instance.restore_node(node);
+    report.restore_operations += 1;
     status_with
 }
 
+fn branch_on_edge(
+    edge: EdgeIdx,
+    instance: &mut Instance,
+    state: &mut State,
+    report: &mut Report,
+) -> Status {
+    trace!("Branching on edge {}", edge);
+    count_branching_step(state, report);
+    record_search_outcome(state, SearchTreeOutcome::Branched);
+
+    // The i-th branch includes the i-th node of the edge and discards all
+    // nodes before it. This way, no hitting set is explored more than once.
+    let nodes: Vec<_> = instance.edge(edge).collect();
+    let mut num_discarded = 0;
+    let mut status = Status::Continue;
+    for &node in &nodes {
+        instance.delete_node(node);
+        num_discarded += 1;
+
+        instance.delete_incident_edges(node);
+        state.partial_hs.push(node);
+        report.include_branches += 1;
+        enter_search_node(state, Some((node, true)));
+        status = solve_recursive(instance, state, report);
+        leave_search_node(state);
+        debug_assert_eq!(state.partial_hs.last().copied(), Some(node));
+        state.partial_hs.pop();
+        instance.restore_incident_edges(node);
+        report.restore_operations += 1;
+
+        if status == Status::Stop {
+            break;
+        }
+
+        // The node stays deleted for the remaining branches. If this leaves
+        // an edge without any nodes, none of them can produce a hitting set.
+        if instance
+            .node(node)
+            .any(|edge| instance.edge_size(edge) == 0)
+        {
+            break;
+        }
+    }
+
+    for &node in nodes[..num_discarded].iter().rev() {
+        instance.restore_node(node);
+    }
+    report.restore_operations += num_discarded;
+    status
+}
+
+/// Branches on the node or edge picked by the branching mode.
+fn branch(instance: &mut Instance, state: &mut State, report: &mut Report) -> Status {
+    match report.settings.branching_mode {
+        BranchingMode::Node => {
+            let nodes = instance.nodes().iter().copied();
+            let node = if report.settings.lowest_index_tie_break {
+                nodes.max_by_key(|&node| (instance.node_degree(node), Reverse(node)))
+            } else {
+                nodes.max_by_key(|&node| instance.node_degree(node))
+            }
+            .expect("Branching on an empty instance");
+            info!("branching");
+            branch_on(node, instance, state, report)
+        }
+        BranchingMode::Edge => {
+            let edges = instance.edges().iter().copied();
+            let edge = if report.settings.lowest_index_tie_break {
+                edges.min_by_key(|&edge| (instance.edge_size(edge), edge))
+            } else {
+                edges.min_by_key(|&edge| instance.edge_size(edge))
+            }
+            .expect("Branching on an empty instance");
+            branch_on_edge(edge, instance, state, report)
+        }
+        BranchingMode::Balanced => branch_on_balanced(instance, state, report),
+    }
+}
+
+/// Branches on the node whose branches leave the most similar numbers of
+/// edges. Including the node removes its edges, discarding it keeps them.
+fn branch_on_balanced(instance: &mut Instance, state: &mut State, report: &mut Report) -> Status {
+    let num_edges = instance.num_edges();
+    let imbalance = |node| (2 * instance.node_degree(node)).abs_diff(num_edges);
+    let nodes = instance.nodes().iter().copied();
+    let node = if report.settings.lowest_index_tie_break {
+        nodes.min_by_key(|&node| (imbalance(node), node))
+    } else {
+        nodes.min_by_key(|&node| imbalance(node))
+    }
+    .expect("Branching on an empty instance");
+    branch_on(node, instance, state, report)
+}
+
 fn solve_recursive(instance: &mut Instance, state: &mut State, report: &mut Report) -> Status {
     let now = Instant::now();
-    if (now - state.last_log_time).as_secs() >= ITERATION_LOG_INTERVAL_SECS {
+    let log_interval_secs = report
+        .settings
+        .progress_interval_secs
+        .unwrap_or(ITERATION_LOG_INTERVAL_SECS);
+    if (now - state.last_log_time).as_secs() >= log_interval_secs {
         info!(
-            "Running on {} for {} branching steps",
-            &report.file_name, report.branching_steps
+            "Running on {} for {} branching steps, best HS has size {}, root lower bound is {}",
+            &report.file_name,
+            report.branching_steps,
+            state.minimum_hs.len(),
+            report.root_bounds.lower_bound()
         );
         state.last_log_time = now;
+        report.progress_messages += 1;
     }
 
     if state.term.load(Ordering::Relaxed) {
@@ -74,10 +301,29 @@ fn solve_recursive(instance: &mut Instance, state: &mut State, report: &mut Repo
         return Status::Stop;
     }
 
+    if let Some(max_restore_operations) = report.settings.max_restore_operations {
+        if report.restore_operations >= max_restore_operations {
+            info!(
+                "Restore budget exhausted, terminating with a HS of size {}",
+                state.minimum_hs.len()
+            );
+            return Status::Stop;
+        }
+    }
+
     info!("reduction");
     let (reduction_result, reduction) = reductions::reduce(instance, state, report);
+    if report.kernel_edges.is_none() {
+        // The first reduction is the one at the root
+        report.kernel_nodes = Some(instance.nodes().len());
+        report.kernel_edges = Some(instance.num_edges());
+    }
+    let frame_memory = SEARCH_FRAME_BYTES + reduction.heap_size();
+    state.search_memory += frame_memory;
+    report.max_search_depth = report.max_search_depth.max(state.search_path_len - 1);
     let status = match reduction_result {
         ReductionResult::Solved => {
+            record_search_outcome(state, SearchTreeOutcome::Solved);
             if state.partial_hs.len() < state.minimum_hs.len() {
                 info!("Found HS of size {} by branching", state.partial_hs.len());
                 state.minimum_hs.clear();
@@ -95,61 +341,129 @@ fn solve_recursive(instance: &mut Instance, state: &mut State, report: &mut Repo
                 );
             }
 
-            if state.minimum_hs.len() <= report.settings.stop_at {
+            if state.minimum_hs.len() <= state.stop_at {
                 Status::Stop
             } else {
                 Status::Continue
             }
         }
-        ReductionResult::Unsolvable => Status::Continue,
+        ReductionResult::Unsolvable => {
+            record_search_outcome(state, SearchTreeOutcome::Pruned);
+            Status::Continue
+        }
         ReductionResult::Stop => Status::Stop,
-        ReductionResult::Finished => {
-            let node = instance
-                .nodes()
-                .iter()
-                .copied()
-                .max_by_key(|&node| instance.node_degree(node))
-                .expect("Branching on an empty instance");
-            info!("branching");
-            branch_on(node, instance, state, report)
+        ReductionResult::Finished
+            if report
+                .settings
+                .memory_limit
+                .is_some_and(|limit| state.search_memory > limit) =>
+        {
+            info!(
+                "Memory limit exceeded, terminating with a HS of size {}",
+                state.minimum_hs.len()
+            );
+            Status::Stop
         }
+        ReductionResult::Finished => branch(instance, state, report),
     };
 
     reduction.restore(instance, &mut state.partial_hs);
+    report.restore_operations += reduction.num_items();
+    state.search_memory -= frame_memory;
     status
 }
 
-fn is_hitting_set(hs: &[NodeIdx], instance: &Instance) -> bool {
-    let hs_set: IdxHashSet<_> = hs.iter().copied().collect();
-    instance
-        .edges()
-        .iter()
-        .all(|&edge| instance.edge(edge).any(|node| hs_set.contains(&node)))
+pub fn is_hitting_set(hs: &[NodeIdx], instance: &Instance) -> bool {
+    instance.uncovered_edges(hs).is_empty()
+}
+
+/// Turns a possibly outdated hitting set into a valid one by dropping nodes
+/// that do not exist (anymore) and adding a node of maximum degree for each
+/// edge that is still not hit.
+fn repair_hitting_set(instance: &Instance, raw_hs: &[u32]) -> Vec<NodeIdx> {
+    let mut is_chosen = vec![false; instance.num_nodes_total()];
+    let mut hs = Vec::new();
+    for &raw_node in raw_hs {
+        let node = NodeIdx::from(raw_node);
+        if node.idx() < instance.num_nodes_total() && !is_chosen[node.idx()] {
+            is_chosen[node.idx()] = true;
+            hs.push(node);
+        }
+    }
+    let num_dropped = raw_hs.len() - hs.len();
+
+    let mut num_added = 0;
+    for edge in instance.uncovered_edges(&hs) {
+        if instance.edge(edge).any(|node| is_chosen[node.idx()]) {
+            continue;
+        }
+        let node = instance
+            .edge(edge)
+            .max_by_key(|&node| instance.node_degree(node))
+            .expect("Empty edge in instance");
+        is_chosen[node.idx()] = true;
+        hs.push(node);
+        num_added += 1;
+    }
+
+    info!(
+        "Repaired initial hitting set by dropping {} and adding {} nodes",
+        num_dropped, num_added
+    );
+    hs
 }
 
 fn get_initial_hitting_set(instance: &Instance, settings: &Settings) -> Result<Vec<NodeIdx>> {
-    if let Some(initial_hs) = &settings.initial_hitting_set {
+    if let Some(raw_initial_hs) = &settings.initial_hitting_set {
         info!("Using initial hitting set from settings");
         debug!("Validating initial hitting set from settings");
-        for &node in initial_hs {
-            ensure!(
-                node.idx() < instance.num_nodes_total(),
-                "node index {} out of bounds in initial hitting set",
-                node
-            );
+        if settings.repair_initial_hitting_set {
+            return Ok(repair_hitting_set(instance, raw_initial_hs));
         }
+        let initial_hs = instance
+            .node_indices_from(raw_initial_hs)
+            .context("invalid initial hitting set")?;
+        let uncovered_edges = instance.uncovered_edges(&initial_hs);
         ensure!(
-            is_hitting_set(initial_hs, instance),
-            "initial hitting set is not valid"
+            uncovered_edges.is_empty(),
+            "initial hitting set is not valid, it misses {} edges",
+            uncovered_edges.len()
         );
 
-        Ok(initial_hs.clone())
+        Ok(initial_hs)
     } else {
-        Ok(instance.nodes().to_vec())
+        Ok(trivial_hitting_set(instance, settings))
     }
 }
 
-fn calculate_root_bounds(instance: &Instance, settings: &Settings) -> RootBounds {
+/// Hitting set used without any better one, either all nodes or, if
+/// `trivial_initial_hitting_set` is set, one node of each edge not hit yet.
+fn trivial_hitting_set(instance: &Instance, settings: &Settings) -> Vec<NodeIdx> {
+    if settings.trivial_initial_hitting_set {
+        instance.trivial_hitting_set()
+    } else {
+        instance.nodes().to_vec()
+    }
+}
+
+/// Upper bound hitting set used at the root. Without greedy upper bounds
+/// (`GreedyMode::Never`) this is a trivial hitting set, so that the search
+/// has no head start.
+fn get_root_upper_bound_hitting_set(instance: &Instance, settings: &Settings) -> Vec<NodeIdx> {
+    if settings.greedy_mode == GreedyMode::Never {
+        trivial_hitting_set(instance, settings)
+    } else if settings.strong_root_greedy {
+        reductions::calc_strong_greedy_approximation(instance)
+    } else {
+        reductions::calc_greedy_approximation(instance, settings.greedy_strategy)
+    }
+}
+
+fn calculate_root_bounds(
+    instance: &Instance,
+    settings: &Settings,
+    greedy_upper: usize,
+) -> RootBounds {
     let num_nodes = instance.num_nodes_total();
     let root_packing = PackingBound::new(instance, settings);
     RootBounds {
@@ -159,48 +473,546 @@ fn calculate_root_bounds(instance: &Instance, settings: &Settings) -> RootBounds
             .0
             .round()
             .unwrap_or(num_nodes),
+        fractional_packing: lower_bound::calc_fractional_packing_bound(instance)
+            .round()
+            .unwrap_or(num_nodes),
         packing: root_packing.bound(),
         sum_over_packing: root_packing.calc_sum_over_packing_bound(instance),
-        greedy_upper: reductions::calc_greedy_approximation(instance).len(),
+        greedy_upper,
     }
 }
 
-pub fn solve(
-    mut instance: Instance,
+/// Calculates all lower bounds and the greedy upper bound for the instance
+/// without solving it.
+pub fn only_bounds(instance: &Instance, settings: &Settings) -> RootBounds {
+    let greedy_hs = get_root_upper_bound_hitting_set(instance, settings);
+    calculate_root_bounds(instance, settings, greedy_hs.len())
+}
+
+/// Solves the instance `warmup + runs` times and summarizes the runtimes of
+/// all but the warmup runs.
+pub fn bench_runs(
+    instance: &Instance,
+    file_name: &str,
+    settings: &Settings,
+    warmup: usize,
+    runs: usize,
+) -> Result<BenchStats> {
+    ensure!(runs > 0, "at least one measured run is required");
+    let mut reports = Vec::with_capacity(runs);
+    for run in 0..warmup + runs {
+        info!("Starting run {} of {}", run + 1, warmup + runs);
+        let (_, report) = solve(instance.clone(), file_name.to_string(), settings.clone())?;
+        if run >= warmup {
+            reports.push(report);
+        }
+    }
+
+    // The solver is deterministic, so all runs that finished the search
+    // should take the same steps. Runs stopped early, e.g. by a signal, can
+    // end anywhere.
+    let mut finished = reports.iter().filter(|report| report.proven_optimal);
+    let first = finished.next().unwrap_or(&reports[0]);
+    ensure!(
+        finished.all(
+            |report| report.opt == first.opt && report.branching_steps == first.branching_steps
+        ),
+        "runs differ in their results"
+    );
+    let mut runtimes: Vec<_> = reports.iter().map(|report| report.runtimes.total).collect();
+    runtimes.sort_unstable();
+    Ok(BenchStats {
+        opt: first.opt,
+        runs,
+        branching_steps: first.branching_steps,
+        min_runtime: runtimes[0],
+        median_runtime: runtimes[runtimes.len() / 2],
+        max_runtime: runtimes[runtimes.len() - 1],
+    })
+}
+
+/// Solves each connected component separately and combines the results.
+///
+/// Components share no edges, so the union of minimum hitting sets of all
+/// components is a minimum hitting set of the whole instance. The combined
+/// report sums the counters and root bounds of the components, but does not
+/// include the detailed runtime and reduction statistics.
+///
+/// All components share the termination flag and the restore budget, so a
+/// signal or an exhausted budget also stops the remaining components. They
+/// are solved one after the other, so the memory limit holds for each.
+fn solve_decomposed(
+    instance: &Instance,
+    components: &[Vec<EdgeIdx>],
     file_name: String,
     settings: Settings,
+    term: &Arc<AtomicBool>,
 ) -> Result<(Vec<NodeIdx>, Report)> {
-    let initial_hs = get_initial_hitting_set(&instance, &settings)?;
-    let root_bounds = calculate_root_bounds(&instance, &settings);
+    let time_before = Instant::now();
+    ensure!(
+        settings.initial_hitting_set.is_none() && settings.stop_at == 0,
+        "initial hitting set and stopping size are not supported for separate components"
+    );
+    if settings.max_gap.is_some() {
+        warn!("Maximum gap is ignored for separate components");
+    }
+    // The gaps of the components would add up, so they are all solved exactly
+    let component_settings = Settings {
+        decompose_components: false,
+        initial_hitting_set: None,
+        stop_at: 0,
+        max_gap: None,
+        search_tree_depth: None,
+        ..settings.clone()
+    };
+
+    info!("Solving {} components separately", components.len());
+    let mut report = new_report(instance, file_name, settings, RootBounds::default());
+    report.proven_optimal = true;
+    let mut hs = Vec::new();
+    let mut root_lower_bound = 0;
+    for (idx, component) in components.iter().enumerate() {
+        let (sub_instance, nodes) = instance.sub_instance(component)?;
+        let remaining_restore_operations = report
+            .settings
+            .max_restore_operations
+            .map(|max| max.saturating_sub(report.restore_operations));
+        let (sub_hs, sub_report) = solve_with_term(
+            sub_instance,
+            format!("{} (component {})", report.file_name, idx),
+            Settings {
+                max_restore_operations: remaining_restore_operations,
+                ..component_settings.clone()
+            },
+            term,
+        )?;
+        hs.extend(sub_hs.iter().map(|&node| nodes[node.idx()]));
+        report.components.push(ComponentReport {
+            num_nodes: nodes.len(),
+            num_edges: component.len(),
+            opt: sub_report.opt,
+            proven_optimal: sub_report.proven_optimal,
+            branching_steps: sub_report.branching_steps,
+            runtime: sub_report.runtimes.total,
+        });
+
+        report.branching_steps += sub_report.branching_steps;
+        report.include_branches += sub_report.include_branches;
+        report.discard_branches += sub_report.discard_branches;
+        report.max_search_depth = report.max_search_depth.max(sub_report.max_search_depth);
+        report.restore_operations += sub_report.restore_operations;
+        report.progress_messages += sub_report.progress_messages;
+        report.incidence_traversals = sub_report
+            .incidence_traversals
+            .map(|traversals| traversals + report.incidence_traversals.unwrap_or(0));
+        report.proven_optimal &= sub_report.proven_optimal;
+        root_lower_bound += sub_report.root_bounds.lower_bound();
+        report.root_bounds.add_component(&sub_report.root_bounds);
+    }
+
+    report.opt = hs.len();
+    report.runtimes.total = time_before.elapsed();
+    report.set_effective_branching_factor();
+    if !report.proven_optimal && root_lower_bound > 0 {
+        #[allow(clippy::cast_precision_loss)]
+        let certified_ratio = report.opt as f64 / root_lower_bound as f64;
+        report.certified_ratio = certified_ratio;
+    }
+    assert!(is_hitting_set(&hs, instance));
+    info!(
+        "Found hitting set of size {} for {} components in {:.2?}",
+        report.opt,
+        components.len(),
+        report.runtimes.total
+    );
+    Ok((hs, report))
+}
+
+/// Reduces the instance at the root and solves the kernel as a new instance.
+///
+/// Deleted nodes and edges are only skipped by the incidence lists, so every
+/// iteration during the search still passes over them. Rebuilding the kernel
+/// with contiguous indices removes them once, at the cost of one copy. The
+/// report describes the search on the kernel, only its size and the settings
+/// refer to the whole instance.
+fn solve_compacted(
+    instance: Instance,
+    file_name: String,
+    settings: Settings,
+    term: &Arc<AtomicBool>,
+) -> Result<(Vec<NodeIdx>, Report)> {
+    let kernel_settings = Settings {
+        compact_kernel: false,
+        ..settings.clone()
+    };
+    let (kernel, reduction_result, partial_hs) =
+        only_reduce(instance.clone(), file_name.clone(), kernel_settings.clone())?;
+    if !matches!(
+        reduction_result,
+        ReductionResult::Solved | ReductionResult::Finished
+    ) {
+        // The initial hitting set or stopping size already ended the
+        // reduction, so there is no kernel to solve
+        return solve_with_term(instance, file_name, kernel_settings, term);
+    }
+
+    let (compacted, nodes) = kernel.sub_instance(&kernel.edges_sorted())?;
+    info!(
+        "Compacted kernel to {} nodes and {} edges after picking {} nodes",
+        compacted.num_nodes_total(),
+        compacted.num_edges_total(),
+        partial_hs.len()
+    );
+    if settings.initial_hitting_set.is_some() {
+        warn!("Initial hitting set is ignored when solving the compacted kernel");
+    }
+    let compacted_settings = Settings {
+        initial_hitting_set: None,
+        stop_at: settings.stop_at.saturating_sub(partial_hs.len()),
+        ..kernel_settings
+    };
+    let (compacted_hs, mut report) =
+        solve_with_term(compacted, file_name, compacted_settings, term)?;
+    let mut hs = partial_hs;
+    hs.extend(compacted_hs.iter().map(|&node| nodes[node.idx()]));
+    report.opt = hs.len();
+    report.settings = settings;
+    assert!(is_hitting_set(&hs, &instance));
+    Ok((hs, report))
+}
+
+/// Solves the sub-instance of the `core_size` smallest edges and returns its
+/// solution if it also hits all other edges, along with the report of the
+/// search on the core.
+///
+/// A minimum hitting set of some of the edges is no larger than one of all
+/// edges, so it is minimum for the whole instance whenever it is valid there.
+fn solve_core(
+    instance: &Instance,
+    core_size: usize,
+    file_name: &str,
+    settings: &Settings,
+    term: &Arc<AtomicBool>,
+) -> Result<(Option<Vec<NodeIdx>>, Report)> {
+    let mut core = instance.edges_sorted_by_size();
+    core.truncate(core_size);
+    core.sort_unstable();
+    let (core_instance, nodes) = instance.sub_instance(&core)?;
+    let core_settings = Settings {
+        core_size: None,
+        initial_hitting_set: None,
+        stop_at: 0,
+        approximation_ratio: None,
+        max_gap: None,
+        search_tree_depth: None,
+        ..settings.clone()
+    };
+    let (core_hs, mut report) = solve_with_term(
+        core_instance,
+        format!("{file_name} (core)"),
+        core_settings,
+        term,
+    )?;
+    let hs: Vec<_> = core_hs.iter().map(|&node| nodes[node.idx()]).collect();
+    if !report.proven_optimal {
+        info!(
+            "Search on the {} smallest edges stopped before proving a minimum, solving all edges",
+            core.len()
+        );
+        return Ok((None, report));
+    }
+    if !is_hitting_set(&hs, instance) {
+        info!(
+            "Hitting set of size {} for the {} smallest edges misses other edges",
+            hs.len(),
+            core.len()
+        );
+        return Ok((None, report));
+    }
+
+    info!(
+        "Minimum hitting set of size {} for the {} smallest edges hits all {} edges",
+        hs.len(),
+        core.len(),
+        instance.num_edges()
+    );
+    report.file_name = file_name.to_string();
+    report.settings = settings.clone();
+    Ok((Some(hs), report))
+}
+
+/// Report for the search on the instance before any solving.
+fn new_report(
+    instance: &Instance,
+    file_name: String,
+    settings: Settings,
+    root_bounds: RootBounds,
+) -> Report {
     let packing_from_scratch_limit = settings.packing_from_scratch_limit;
-    let mut report = Report {
+    Report {
         file_name,
-        opt: initial_hs.len(),
+        metadata: instance.metadata().clone(),
+        opt: 0,
         branching_steps: 0,
+        branching_steps_until_final_improvement: 0,
+        include_branches: 0,
+        discard_branches: 0,
+        max_search_depth: 0,
+        effective_branching_factor: 1.0,
+        restore_operations: 0,
+        progress_messages: 0,
+        certified_ratio: 1.0,
+        proven_optimal: false,
+        unique_minimum: None,
+        treewidth_upper_bound: None,
+        disjoint_edge_pairs: None,
+        nodes_only_in_large_edges: None,
+        incidence_traversals: None,
+        kernel_nodes: None,
+        kernel_edges: None,
+        kernel_ratio: None,
         settings,
         root_bounds,
         runtimes: RuntimeStats::default(),
         reductions: ReductionStats::new(packing_from_scratch_limit),
         upper_bound_improvements: Vec::new(),
-    };
+        branching_samples: Vec::new(),
+        components: Vec::new(),
+        search_tree: None,
+    }
+}
+
+/// Logs the initial gap and calculates the optional structural statistics
+/// of the instance requested by the settings.
+fn calculate_diagnostics(instance: &Instance, report: &mut Report) {
+    let root_bounds = &report.root_bounds;
+    let initial_gap = root_bounds.initial_gap();
+    info!(
+        "Initial gap is {} (greedy {}, lower bound {}), instance looks {}",
+        initial_gap,
+        root_bounds.greedy_upper,
+        root_bounds.lower_bound(),
+        root_bounds.difficulty()
+    );
+    let settings = &report.settings;
+    if settings
+        .warn_initial_gap
+        .is_some_and(|threshold| initial_gap >= threshold)
+    {
+        warn!(
+            "Greedy hitting set is {} nodes larger than the root lower bound, solving may take long",
+            initial_gap
+        );
+    }
+    report.treewidth_upper_bound = settings.estimate_treewidth.then(|| {
+        let treewidth = instance.treewidth_upper_bound();
+        info!("Treewidth is at most {}", treewidth);
+        treewidth
+    });
+    report.disjoint_edge_pairs = settings.count_disjoint_edge_pairs.then(|| {
+        let disjoint_edge_pairs = instance.disjoint_edge_pairs();
+        info!(
+            "Instance has {} pairs of disjoint edges",
+            disjoint_edge_pairs
+        );
+        disjoint_edge_pairs
+    });
+    report.nodes_only_in_large_edges = settings.large_edge_threshold.map(|threshold| {
+        let num_nodes = instance.nodes_only_in_large_edges(threshold).len();
+        info!(
+            "Instance has {} nodes only in edges with more than {} nodes",
+            num_nodes, threshold
+        );
+        num_nodes
+    });
+}
+
+/// Size up to which a found hitting set ends the search, given by the
+/// stopping size, the approximation ratio and the maximum gap.
+fn stopping_size(settings: &Settings, root_lower_bound: usize) -> Result<usize> {
+    let mut stop_at = settings.stop_at;
+    if let Some(ratio) = settings.approximation_ratio {
+        ensure!(
+            ratio >= 1.0,
+            "approximation ratio must be at least 1, got {}",
+            ratio
+        );
+        // Any hitting set of at most this size is within the ratio of a minimum one
+        #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+        let approximate_stop_at = (ratio * root_lower_bound as f64).floor() as usize;
+        stop_at = stop_at.max(approximate_stop_at);
+    }
+    if let Some(max_gap) = settings.max_gap {
+        stop_at = stop_at.max(root_lower_bound + max_gap);
+    }
+    Ok(stop_at)
+}
+
+/// Derives the summary statistics of the report once the search is over.
+fn finish_report(
+    report: &mut Report,
+    instance: &Instance,
+    status: Status,
+    root_lower_bound: usize,
+) {
+    report.runtimes.set_branching_remainder();
+    report.set_effective_branching_factor();
+    if let (Some(first), Some(last)) = (
+        report.upper_bound_improvements.first(),
+        report.upper_bound_improvements.last(),
+    ) {
+        report.runtimes.until_first_improvement = first.runtime;
+        report.runtimes.until_final_improvement = last.runtime;
+        report.branching_steps_until_final_improvement = last.branching_steps;
+    }
+    if let Some(kernel_edges) = report.kernel_edges {
+        #[allow(clippy::cast_precision_loss)]
+        let kernel_ratio = if instance.num_edges() == 0 {
+            1.0
+        } else {
+            kernel_edges as f64 / instance.num_edges() as f64
+        };
+        report.kernel_ratio = Some(kernel_ratio);
+    }
+    report.proven_optimal = status == Status::Continue || report.opt <= root_lower_bound;
+    if status == Status::Stop && root_lower_bound > 0 {
+        #[allow(clippy::cast_precision_loss)]
+        let certified_ratio = report.opt as f64 / root_lower_bound as f64;
+        report.certified_ratio = certified_ratio;
+    }
+}
+
+pub fn solve(
+    instance: Instance,
+    file_name: String,
+    settings: Settings,
+) -> Result<(Vec<NodeIdx>, Report)> {
+    let term = Arc::new(AtomicBool::new(false));
+    // Unregistered again below, so that repeated solves do not pile up flags
+    let signal_id = signal_hook::flag::register(SIGUSR1, Arc::clone(&term))?;
+    let result = solve_with_term(instance, file_name, settings, &term);
+    signal_hook::low_level::unregister(signal_id);
+    result
+}
+
+/// Solves the instance, stopping early once `term` is set.
+fn solve_with_term(
+    instance: Instance,
+    file_name: String,
+    settings: Settings,
+    term: &Arc<AtomicBool>,
+) -> Result<(Vec<NodeIdx>, Report)> {
+    if settings.decompose_components {
+        let components = instance.connected_components();
+        if components.len() > 1 {
+            return solve_decomposed(&instance, &components, file_name, settings, term);
+        }
+    }
+    if settings.compact_kernel {
+        return solve_compacted(instance, file_name, settings, term);
+    }
+    let mut settings = settings;
+    if let Some(core_size) = settings.core_size {
+        if core_size < instance.num_edges() {
+            let (core_hs, core_report) =
+                solve_core(&instance, core_size, &file_name, &settings, term)?;
+            if let Some(hs) = core_hs {
+                return Ok((hs, core_report));
+            }
+            // The search on all edges only gets the rest of the restore budget
+            settings.max_restore_operations = settings
+                .max_restore_operations
+                .map(|max| max.saturating_sub(core_report.restore_operations));
+        }
+    }
+    solve_whole(instance, file_name, settings, term)
+}
+
+/// Solves the instance as a whole by branch and bound.
+fn solve_whole(
+    mut instance: Instance,
+    file_name: String,
+    settings: Settings,
+    term: &Arc<AtomicBool>,
+) -> Result<(Vec<NodeIdx>, Report)> {
+    let traversals_before = incidence_traversals();
+    if instance.is_graph() {
+        info!("Instance is a graph, solving minimum vertex cover");
+    }
+    let initial_hs = get_initial_hitting_set(&instance, &settings)?;
+    let time_before_greedy = Instant::now();
+    let greedy_hs = get_root_upper_bound_hitting_set(&instance, &settings);
+    let root_greedy_time = time_before_greedy.elapsed();
+    let root_bounds = calculate_root_bounds(&instance, &settings, greedy_hs.len());
+    let root_lower_bound = root_bounds.lower_bound();
+    let stop_at = stopping_size(&settings, root_lower_bound)?;
+    let mut report = new_report(&instance, file_name, settings, root_bounds);
+    report.opt = initial_hs.len();
+    report.runtimes.root_greedy = root_greedy_time;
+    calculate_diagnostics(&instance, &mut report);
 
     let mut state = State {
         partial_hs: Vec::new(),
         minimum_hs: initial_hs,
         last_log_time: Instant::now(),
         solve_start_time: Instant::now(),
-        term: Arc::new(AtomicBool::new(false)),
+        term: Arc::clone(term),
+        stop_at,
+        vertex_domination_skip: AdaptiveSkip::default(),
+        edge_domination_skip: AdaptiveSkip::default(),
+        search_memory: 0,
+        search_path_len: 0,
+        search_tree: report
+            .settings
+            .search_tree_depth
+            .map(SearchTreeRecorder::new),
     };
 
-    signal_hook::flag::register(SIGUSR1, Arc::clone(&state.term))?;
-
-    let status = solve_recursive(&mut instance, &mut state, &mut report);
+    // Check that branching restores the instance exactly in debug builds
+    let instance_before = cfg!(debug_assertions).then(|| instance.clone());
+    let num_nodes_before = instance.nodes().len();
+    let num_edges_before = instance.edges().len();
+    let status = if greedy_hs.len() <= root_lower_bound {
+        // The greedy hitting set is provably minimum, no need to branch
+        info!(
+            "Greedy hitting set of size {} meets the root lower bound",
+            greedy_hs.len()
+        );
+        if greedy_hs.len() < state.minimum_hs.len() {
+            state.minimum_hs = greedy_hs;
+            report.upper_bound_improvements.push(UpperBoundImprovement {
+                new_bound: state.minimum_hs.len(),
+                branching_steps: 0,
+                runtime: state.solve_start_time.elapsed(),
+            });
+        }
+        Status::Continue
+    } else {
+        enter_search_node(&mut state, None);
+        let status = solve_recursive(&mut instance, &mut state, &mut report);
+        leave_search_node(&mut state);
+        status
+    };
     report.runtimes.total = state.solve_start_time.elapsed();
+    report.incidence_traversals = incidence_traversals()
+        .zip(traversals_before)
+        .map(|(after, before)| after - before);
+    if let Some(instance_before) = instance_before {
+        let differences = instance.diff(&instance_before);
+        debug_assert!(
+            differences.is_empty(),
+            "Instance not restored after solving: {differences:?}"
+        );
+        if let Err(error) = instance.check_degrees() {
+            panic!("Inconsistent degrees after solving: {error:#}");
+        }
+    }
     report.opt = state.minimum_hs.len();
+    finish_report(&mut report, &instance, status, root_lower_bound);
 
     info!("Validating found hitting set");
-    assert_eq!(instance.num_nodes_total(), instance.nodes().len());
-    assert_eq!(instance.num_edges_total(), instance.edges().len());
+    assert_eq!(num_nodes_before, instance.nodes().len());
+    assert_eq!(num_edges_before, instance.edges().len());
     assert!(is_hitting_set(&state.minimum_hs, &instance));
 
     if status == Status::Continue {
@@ -213,30 +1025,199 @@ pub fn solve(
             "Found hitting set of size {} in {:.2?} and {} branching steps",
             state.minimum_hs.len(), report.runtimes.total, report.branching_steps
         );
+        info!(
+            "Hitting set is within a factor of {:.3} of a minimum one",
+            report.certified_ratio
+        );
     }
     debug!("Final HS (size {}): {:?}", report.opt, &state.minimum_hs);
 
+    report.search_tree = state.search_tree.take().and_then(|tree| tree.root);
     Ok((state.minimum_hs, report))
 }
 
+/// Settings for solving modified copies of an instance that only need to
+/// know whether a hitting set of at most `size` nodes exists.
+fn existence_settings(settings: &Settings, size: usize) -> Settings {
+    Settings {
+        initial_hitting_set: None,
+        decompose_components: false,
+        stop_at: size,
+        max_restore_operations: None,
+        memory_limit: None,
+        approximation_ratio: None,
+        max_gap: None,
+        warn_initial_gap: None,
+        search_tree_depth: None,
+        ..settings.clone()
+    }
+}
+
+/// Whether the instance has a hitting set of at most `size` nodes.
+///
+/// Fails if the search stops (e.g. on `SIGUSR1`) before finding such a
+/// hitting set or proving that there is none.
+fn has_hitting_set_of_size(instance: Instance, size: usize, settings: &Settings) -> Result<bool> {
+    if instance.num_edges() == 0 {
+        return Ok(true);
+    }
+    if size == 0
+        || instance
+            .edges()
+            .iter()
+            .any(|&edge| instance.edge_size(edge) == 0)
+    {
+        return Ok(false);
+    }
+
+    let (hs, report) = solve(
+        instance,
+        format!("existence of size {size}"),
+        existence_settings(settings, size),
+    )?;
+    ensure!(
+        hs.len() <= size || report.proven_optimal,
+        "search for a hitting set of size {} stopped before it was decided",
+        size
+    );
+    Ok(hs.len() <= size)
+}
+
+/// Checks whether `minimum_hs` is the only minimum hitting set.
+///
+/// For every node of the hitting set, this solves the instance without that
+/// node, stopping as soon as a hitting set of the same size is found.
+pub fn is_unique_minimum(
+    instance: &Instance,
+    minimum_hs: &[NodeIdx],
+    settings: &Settings,
+) -> Result<bool> {
+    for &node in minimum_hs {
+        let mut sub_instance = instance.clone();
+        sub_instance.delete_node(node);
+        info!("Searching for a minimum hitting set without node {}", node);
+        if has_hitting_set_of_size(sub_instance, minimum_hs.len(), settings)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Finds the minimum hitting set that is lexicographically smallest when
+/// sorted, given the minimum size `opt`.
+///
+/// Nodes are decided in order of increasing index: a node is included if a
+/// minimum hitting set with all nodes included so far and this one exists.
+/// This solves the instance once per node in the worst case.
+pub fn lexicographically_smallest_minimum(
+    instance: &Instance,
+    opt: usize,
+    settings: &Settings,
+) -> Result<Vec<NodeIdx>> {
+    let mut remaining = instance.clone();
+    let mut lexmin = Vec::new();
+    for node in instance.nodes_sorted() {
+        if remaining.num_edges() == 0 {
+            break;
+        }
+
+        let mut with_node = remaining.clone();
+        with_node.delete_node(node);
+        with_node.delete_incident_edges(node);
+        debug!("Checking if node {} is in a minimum hitting set", node);
+        if has_hitting_set_of_size(with_node.clone(), opt - lexmin.len() - 1, settings)? {
+            remaining = with_node;
+            lexmin.push(node);
+        } else {
+            remaining.delete_node(node);
+        }
+    }
+
+    debug_assert!(is_hitting_set(&lexmin, instance));
+    debug_assert_eq!(lexmin.len(), opt);
+    Ok(lexmin)
+}
+
+/// Finds a minimum hitting set whose maximum node degree is as small as
+/// possible, given the minimum size `opt` and a minimum hitting set.
+///
+/// This searches for the smallest degree bound such that a minimum hitting
+/// set remains after deleting all nodes of larger degree, solving the
+/// instance a logarithmic number of times.
+pub fn min_max_degree_minimum(
+    instance: &Instance,
+    minimum_hs: &[NodeIdx],
+    settings: &Settings,
+) -> Result<Vec<NodeIdx>> {
+    let opt = minimum_hs.len();
+    let without_degrees_above = |max_degree| {
+        let mut sub_instance = instance.clone();
+        for node in instance.nodes_sorted() {
+            if instance.node_degree(node) > max_degree {
+                sub_instance.delete_node(node);
+            }
+        }
+        sub_instance
+    };
+
+    let feasible_degree = minimum_hs
+        .iter()
+        .map(|&node| instance.node_degree(node))
+        .max()
+        .unwrap_or(0);
+    let mut degrees: Vec<_> = instance
+        .nodes()
+        .iter()
+        .map(|&node| instance.node_degree(node))
+        .filter(|&degree| degree < feasible_degree)
+        .collect();
+    degrees.sort_unstable();
+    degrees.dedup();
+    degrees.push(feasible_degree);
+
+    // The last degree is known to admit a minimum hitting set
+    let (mut low, mut high) = (0, degrees.len() - 1);
+    while low < high {
+        let mid = usize::midpoint(low, high);
+        debug!(
+            "Checking for a minimum hitting set of maximum degree {}",
+            degrees[mid]
+        );
+        if has_hitting_set_of_size(without_degrees_above(degrees[mid]), opt, settings)? {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    let max_degree = degrees[low];
+    info!(
+        "Minimum hitting set with maximum degree {} exists",
+        max_degree
+    );
+    if max_degree == feasible_degree {
+        return Ok(minimum_hs.to_vec());
+    }
+    let (hs, _) = solve(
+        without_degrees_above(max_degree),
+        format!("maximum degree {max_degree}"),
+        existence_settings(settings, opt),
+    )?;
+    debug_assert!(is_hitting_set(&hs, instance));
+    debug_assert_eq!(hs.len(), opt);
+    Ok(hs)
+}
+
 pub fn only_reduce(
     mut instance: Instance,
     file_name: String,
     settings: Settings,
 ) -> Result<(Instance, ReductionResult, Vec<NodeIdx>)> {
     let initial_hs = get_initial_hitting_set(&instance, &settings)?;
-    let root_bounds = calculate_root_bounds(&instance, &settings);
-    let packing_from_scratch_limit = settings.packing_from_scratch_limit;
-    let mut report = Report {
-        file_name,
-        opt: initial_hs.len(),
-        branching_steps: 0,
-        settings,
-        root_bounds,
-        runtimes: RuntimeStats::default(),
-        reductions: ReductionStats::new(packing_from_scratch_limit),
-        upper_bound_improvements: Vec::new(),
-    };
+    let greedy_upper = get_root_upper_bound_hitting_set(&instance, &settings).len();
+    let root_bounds = calculate_root_bounds(&instance, &settings, greedy_upper);
+    let mut report = new_report(&instance, file_name, settings, root_bounds);
+    report.opt = initial_hs.len();
 
     let mut state = State {
         partial_hs: Vec::new(),
@@ -244,9 +1225,522 @@ pub fn only_reduce(
         last_log_time: Instant::now(),
         solve_start_time: Instant::now(),
         term: Arc::new(AtomicBool::new(false)),
+        stop_at: report.settings.stop_at,
+        vertex_domination_skip: AdaptiveSkip::default(),
+        edge_domination_skip: AdaptiveSkip::default(),
+        search_memory: 0,
+        search_path_len: 0,
+        search_tree: None,
     };
 
     let (reduction_result, _) = reductions::reduce(&mut instance, &mut state, &mut report);
 
     Ok((instance, reduction_result, state.partial_hs))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::LoadOptions;
+
+    fn load(text: &str) -> Instance {
+        Instance::load_from_text(text.as_bytes(), &LoadOptions::default()).unwrap()
+    }
+
+    fn settings() -> Settings {
+        serde_json::from_str(include_str!("../settings.json")).unwrap()
+    }
+
+    fn nodes(raw: &[usize]) -> Vec<NodeIdx> {
+        raw.iter().copied().map(NodeIdx::from).collect()
+    }
+
+    /// Vertex cover of the Petersen graph, which has size 6 and needs
+    /// branching with the default settings
+    const PETERSEN: &str = "10 15\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n2 0 5\n2 1 6\n2 2 7\n\
+                            2 3 8\n2 4 9\n2 5 7\n2 6 8\n2 7 9\n2 5 8\n2 6 9\n";
+
+    #[test]
+    fn unique_minimum_with_forced_node() {
+        // Node 0 is forced by the unit edge, node 2 is the only way to hit
+        // the other two edges with a single node
+        let instance = load("4 3\n1 0\n2 1 2\n2 2 3\n");
+        assert!(is_unique_minimum(&instance, &nodes(&[0, 2]), &settings()).unwrap());
+
+        let instance = load("3 2\n1 0\n2 1 2\n");
+        assert!(!is_unique_minimum(&instance, &nodes(&[0, 1]), &settings()).unwrap());
+    }
+
+    #[test]
+    fn lexicographically_smallest_minimum_of_path() {
+        // Minimum hitting sets are {0, 2}, {1, 2} and {1, 3}
+        let instance = load("4 3\n2 0 1\n2 1 2\n2 2 3\n");
+        let lexmin = lexicographically_smallest_minimum(&instance, 2, &settings()).unwrap();
+        assert_eq!(lexmin, nodes(&[0, 2]));
+    }
+
+    #[test]
+    fn core_first_matches_plain_solve() {
+        // The triangle is a core whose minimum hitting sets hit the large
+        // edge, the first edge of the path is not
+        for (text, core_size) in [
+            ("4 4\n2 0 1\n2 1 2\n2 0 2\n4 0 1 2 3\n", 3),
+            ("5 3\n2 0 1\n2 1 2\n3 2 3 4\n", 1),
+        ] {
+            let (plain_hs, plain_report) = solve(load(text), String::new(), settings()).unwrap();
+            let core_settings = Settings {
+                core_size: Some(core_size),
+                ..settings()
+            };
+            let (core_hs, core_report) = solve(load(text), String::new(), core_settings).unwrap();
+            assert!(is_hitting_set(&core_hs, &load(text)));
+            assert!(core_report.proven_optimal);
+            assert_eq!(core_report.opt, plain_report.opt);
+            assert_eq!(core_hs.len(), plain_hs.len());
+        }
+    }
+
+    #[test]
+    fn core_first_branches_less_when_large_edges_are_hit() {
+        // The large edges make the plain search branch, the minimum hitting
+        // sets of the twelve graph edges already hit them
+        let text = "10 17\n2 0 1\n2 0 3\n2 0 5\n2 0 6\n2 1 2\n2 1 6\n2 1 8\n2 1 9\n2 2 4\n\
+                    2 4 8\n2 5 6\n2 7 9\n4 0 2 5 7\n4 3 6 7 8\n5 0 1 3 5 6\n5 2 4 6 7 9\n\
+                    5 2 5 7 8 9\n";
+        let (_, plain_report) = solve(load(text), String::new(), settings()).unwrap();
+        let core_settings = Settings {
+            core_size: Some(12),
+            ..settings()
+        };
+        let (_, core_report) = solve(load(text), String::new(), core_settings).unwrap();
+        assert!(core_report.proven_optimal);
+        assert_eq!(core_report.opt, plain_report.opt);
+        assert!(core_report.branching_steps < plain_report.branching_steps);
+    }
+
+    #[test]
+    #[cfg(feature = "count-traversals")]
+    fn incidence_traversals_grow_with_harder_instances() {
+        let path = load("4 3\n2 0 1\n2 1 2\n2 2 3\n");
+        let (_, easy) = solve(path, String::new(), settings()).unwrap();
+        let (_, hard) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        let easy = easy.incidence_traversals.unwrap();
+        assert!(easy > 0);
+        assert!(hard.incidence_traversals.unwrap() > easy);
+    }
+
+    #[test]
+    fn node_branching_counts_both_branches() {
+        let (hs, report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        assert_eq!(hs.len(), 6);
+        assert!(report.branching_steps > 0);
+        // Without stopping early, every branching step explores both branches
+        assert_eq!(report.include_branches, report.branching_steps);
+        assert_eq!(report.discard_branches, report.branching_steps);
+
+        // A unit edge forces its node without branching on it
+        let with_unit_edge = PETERSEN.replacen("10 15", "11 16", 1) + "1 10\n";
+        let (hs, report) = solve(load(&with_unit_edge), String::new(), settings()).unwrap();
+        assert_eq!(hs.len(), 7);
+        assert_eq!(report.reductions.forced_vertices_found, 1);
+        assert_eq!(report.include_branches, report.branching_steps);
+    }
+
+    #[test]
+    fn kernel_ratio_of_reduced_and_irreducible_instances() {
+        let (_, report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        assert_eq!(report.kernel_ratio, Some(1.0));
+
+        // The unit edge forces node 0, which hits two more edges
+        let with_unit_edge = PETERSEN.replacen("10 15", "10 16", 1) + "1 0\n";
+        let (_, report) = solve(load(&with_unit_edge), String::new(), settings()).unwrap();
+        let ratio = report.kernel_ratio.unwrap();
+        assert!(ratio > 0.0 && ratio < 1.0);
+    }
+
+    #[test]
+    fn existence_is_decided_on_both_sides_of_the_minimum() {
+        assert!(has_hitting_set_of_size(load(PETERSEN), 6, &settings()).unwrap());
+        assert!(!has_hitting_set_of_size(load(PETERSEN), 5, &settings()).unwrap());
+    }
+
+    #[test]
+    fn decomposed_solve_matches_plain_solve() {
+        // Two disjoint triangles and the Petersen graph, with nodes of the
+        // components interleaved
+        let text = "16 21\n2 0 2\n2 2 4\n2 0 4\n2 1 3\n2 3 5\n2 1 5\n\
+                    2 6 7\n2 7 8\n2 8 9\n2 9 10\n2 6 10\n2 6 11\n2 7 12\n2 8 13\n\
+                    2 9 14\n2 10 15\n2 11 13\n2 12 14\n2 13 15\n2 11 14\n2 12 15\n";
+        let (plain_hs, _) = solve(load(text), String::new(), settings()).unwrap();
+        let decomposed_settings = Settings {
+            decompose_components: true,
+            ..settings()
+        };
+        let (hs, report) = solve(load(text), String::new(), decomposed_settings.clone()).unwrap();
+        assert_eq!(hs.len(), plain_hs.len());
+        assert_eq!(report.opt, 10);
+        assert!(report.proven_optimal);
+        assert_eq!(report.components.len(), 3);
+
+        let stopping_settings = Settings {
+            stop_at: 12,
+            ..decomposed_settings
+        };
+        assert!(solve(load(text), String::new(), stopping_settings).is_err());
+    }
+
+    #[test]
+    fn balanced_branching_reaches_the_optimum() {
+        let instances = [
+            (PETERSEN.to_string(), 6),
+            (
+                "10 17\n2 0 1\n2 0 3\n2 0 5\n2 0 6\n2 1 2\n2 1 6\n2 1 8\n2 1 9\n2 2 4\n\
+                 2 4 8\n2 5 6\n2 7 9\n4 0 2 5 7\n4 3 6 7 8\n5 0 1 3 5 6\n5 2 4 6 7 9\n\
+                 5 2 5 7 8 9\n"
+                    .to_string(),
+                5,
+            ),
+        ];
+        for (text, opt) in instances {
+            let balanced_settings = Settings {
+                branching_mode: BranchingMode::Balanced,
+                ..settings()
+            };
+            let (hs, report) = solve(load(&text), String::new(), balanced_settings).unwrap();
+            assert!(is_hitting_set(&hs, &load(&text)));
+            assert!(report.proven_optimal);
+            assert_eq!(hs.len(), opt);
+            assert!(report.branching_steps > 0);
+        }
+    }
+
+    #[test]
+    fn lowest_index_tie_break_ignores_edge_order() {
+        // All nodes of the Petersen graph have the same degree, so the
+        // branching order only depends on the tie break
+        let mut lines: Vec<_> = PETERSEN.lines().collect();
+        lines[1..].reverse();
+        let reversed = lines.join("\n") + "\n";
+        let tie_break_settings = Settings {
+            lowest_index_tie_break: true,
+            ..settings()
+        };
+        let (hs, report) =
+            solve(load(PETERSEN), String::new(), tie_break_settings.clone()).unwrap();
+        let (reversed_hs, reversed_report) =
+            solve(load(&reversed), String::new(), tie_break_settings).unwrap();
+        assert_eq!(reversed_hs, hs);
+        assert_eq!(reversed_report.branching_steps, report.branching_steps);
+        assert_eq!(reversed_report.max_search_depth, report.max_search_depth);
+    }
+
+    #[test]
+    fn probing_removes_nodes_and_saves_branching() {
+        let text = "16 40\n3 0 4 14\n3 5 12 15\n3 2 8 12\n2 4 9\n3 5 6 14\n3 2 10 15\n\
+                    3 2 13 14\n2 0 2\n2 8 12\n2 9 11\n3 2 7 13\n2 1 9\n2 0 11\n2 6 11\n\
+                    2 6 8\n3 1 11 13\n2 4 14\n2 5 9\n3 8 14 15\n2 9 10\n2 0 1\n3 1 3 8\n\
+                    2 1 5\n2 1 11\n2 1 8\n2 7 9\n3 3 8 12\n3 3 6 15\n2 7 15\n3 1 9 15\n\
+                    2 3 5\n2 3 8\n3 4 10 12\n3 6 14 15\n2 0 6\n3 1 2 15\n2 2 6\n3 2 4 9\n\
+                    3 2 4 6\n3 0 4 5\n";
+        let (plain_hs, plain_report) = solve(load(text), String::new(), settings()).unwrap();
+        let probing_settings = Settings {
+            probing_limit: 20,
+            ..settings()
+        };
+        let (hs, report) = solve(load(text), String::new(), probing_settings).unwrap();
+        assert!(is_hitting_set(&hs, &load(text)));
+        assert_eq!(hs.len(), plain_hs.len());
+        assert!(report.reductions.probing_vertices_found > 0);
+        assert!(report.branching_steps < plain_report.branching_steps);
+    }
+
+    #[test]
+    fn only_bounds_enclose_the_optimum() {
+        let cycle = "5 5\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n";
+        for text in [PETERSEN, cycle, "4 3\n2 0 1\n2 1 2\n2 2 3\n"] {
+            let bounds = only_bounds(&load(text), &settings());
+            let (_, report) = solve(load(text), String::new(), settings()).unwrap();
+            for bound in [
+                bounds.max_degree,
+                bounds.sum_degree,
+                bounds.efficiency,
+                bounds.fractional_packing,
+                bounds.packing,
+                bounds.sum_over_packing,
+            ] {
+                assert!(bound <= report.opt);
+            }
+            assert!(bounds.lower_bound() > 0);
+            assert!(bounds.greedy_upper >= report.opt);
+        }
+    }
+
+    #[test]
+    fn progress_is_logged_at_the_configured_interval() {
+        let (_, report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        assert_eq!(report.progress_messages, 0);
+
+        // With no delay, every search node logs its progress
+        let progress_settings = Settings {
+            progress_interval_secs: Some(0),
+            ..settings()
+        };
+        let (_, report) = solve(load(PETERSEN), String::new(), progress_settings).unwrap();
+        assert!(report.progress_messages > 1);
+        assert!(report.progress_messages > report.branching_steps);
+    }
+
+    #[test]
+    fn min_max_degree_avoids_the_hub() {
+        // Minimum hitting sets are {0, 1} and {0, 3} with the hub of degree 4,
+        // and {1, 2} of maximum degree 3
+        let instance = load("4 5\n2 0 1\n3 0 1 3\n2 0 2\n3 0 2 3\n2 1 3\n");
+        let mut hs = min_max_degree_minimum(&instance, &nodes(&[0, 1]), &settings()).unwrap();
+        hs.sort_unstable();
+        assert_eq!(hs, nodes(&[1, 2]));
+
+        // A hitting set that is already best is kept
+        let hs = min_max_degree_minimum(&instance, &nodes(&[1, 2]), &settings()).unwrap();
+        assert_eq!(hs, nodes(&[1, 2]));
+    }
+
+    #[test]
+    fn bench_runs_agree_with_a_single_solve() {
+        let (_, report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        let stats = bench_runs(&load(PETERSEN), "petersen", &settings(), 1, 3).unwrap();
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.opt, report.opt);
+        assert_eq!(stats.branching_steps, report.branching_steps);
+        assert!(stats.min_runtime <= stats.median_runtime);
+        assert!(stats.median_runtime <= stats.max_runtime);
+
+        assert!(bench_runs(&load(PETERSEN), "petersen", &settings(), 1, 0).is_err());
+    }
+
+    #[test]
+    fn strong_root_greedy_saves_branching() {
+        // The degree greedy finds a vertex cover of size 6, the strong greedy
+        // already a minimum one of size 5
+        let text = "10 15\n2 0 1\n2 3 4\n2 2 7\n2 6 8\n2 1 8\n2 0 9\n2 5 7\n2 2 3\n2 6 7\n\
+                    2 1 7\n2 8 9\n2 5 6\n2 5 9\n2 1 3\n2 4 7\n";
+        let (_, plain_report) = solve(load(text), String::new(), settings()).unwrap();
+        let strong_settings = Settings {
+            strong_root_greedy: true,
+            ..settings()
+        };
+        let (hs, report) = solve(load(text), String::new(), strong_settings).unwrap();
+        assert_eq!(hs.len(), plain_report.opt);
+        assert_eq!(plain_report.root_bounds.greedy_upper, 6);
+        assert_eq!(report.root_bounds.greedy_upper, 5);
+        assert!(report.branching_steps < plain_report.branching_steps);
+    }
+
+    #[test]
+    fn exported_search_tree_respects_max_depth() {
+        fn count(search_node: &serde_json::Value, depth: usize) -> (usize, usize) {
+            let children = search_node["children"].as_array().unwrap();
+            children.iter().map(|child| count(child, depth + 1)).fold(
+                (1, depth),
+                |(num, max_depth), (child_num, child_depth)| {
+                    (num + child_num, max_depth.max(child_depth))
+                },
+            )
+        }
+
+        let full_settings = Settings {
+            search_tree_depth: Some(usize::MAX),
+            ..settings()
+        };
+        let (_, report) = solve(load(PETERSEN), String::new(), full_settings).unwrap();
+        let tree = serde_json::to_value(report.search_tree.unwrap()).unwrap();
+        let (num_search_nodes, depth) = count(&tree, 0);
+        assert_eq!(
+            num_search_nodes,
+            1 + report.include_branches + report.discard_branches
+        );
+        assert_eq!(depth, report.max_search_depth);
+        assert!(tree["node"].is_null());
+
+        let capped_settings = Settings {
+            search_tree_depth: Some(1),
+            ..settings()
+        };
+        let (_, report) = solve(load(PETERSEN), String::new(), capped_settings).unwrap();
+        let tree = serde_json::to_value(report.search_tree.unwrap()).unwrap();
+        let (num_search_nodes, depth) = count(&tree, 0);
+        assert_eq!(depth, 1);
+        assert!(num_search_nodes <= 3);
+        assert!(tree["children"][0]["truncated"].as_bool().unwrap());
+    }
+
+    #[test]
+    fn effective_branching_factor_grows_with_search_nodes() {
+        let (_, report) = solve(
+            load("4 3\n2 0 1\n2 1 2\n2 2 3\n"),
+            String::new(),
+            settings(),
+        )
+        .unwrap();
+        assert_eq!(report.max_search_depth, 0);
+        assert!((report.effective_branching_factor - 1.0).abs() < 1e-9);
+
+        let (_, mut report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        assert!(report.effective_branching_factor >= 1.0);
+
+        // A complete binary tree of depth 3 has 15 search nodes
+        report.max_search_depth = 3;
+        report.include_branches = 7;
+        report.discard_branches = 7;
+        report.set_effective_branching_factor();
+        assert!((report.effective_branching_factor - 15_f64.cbrt()).abs() < 1e-9);
+        let binary_factor = report.effective_branching_factor;
+
+        // More search nodes at the same depth
+        report.include_branches = 20;
+        report.discard_branches = 10;
+        report.set_effective_branching_factor();
+        assert!(report.effective_branching_factor > binary_factor);
+    }
+
+    #[test]
+    fn component_reports_add_up_to_the_whole() {
+        // The Petersen graph and a path of three edges on the nodes after it
+        let text = PETERSEN.replacen("10 15", "14 18", 1) + "2 10 11\n2 11 12\n2 12 13\n";
+        let (plain_hs, _) = solve(load(&text), String::new(), settings()).unwrap();
+        let decomposed_settings = Settings {
+            decompose_components: true,
+            ..settings()
+        };
+        let (_, report) = solve(load(&text), String::new(), decomposed_settings).unwrap();
+        let components = &report.components;
+        assert_eq!(components.len(), 2);
+        let mut opts: Vec<_> = components.iter().map(|component| component.opt).collect();
+        opts.sort_unstable();
+        assert_eq!(opts, [2, 6]);
+        assert_eq!(opts.iter().sum::<usize>(), plain_hs.len());
+        assert_eq!(
+            components
+                .iter()
+                .map(|component| component.num_nodes)
+                .sum::<usize>(),
+            14
+        );
+        assert_eq!(
+            components
+                .iter()
+                .map(|component| component.num_edges)
+                .sum::<usize>(),
+            18
+        );
+        assert!(components.iter().all(|component| component.proven_optimal));
+        assert_eq!(
+            components
+                .iter()
+                .map(|component| component.branching_steps)
+                .sum::<usize>(),
+            report.branching_steps
+        );
+    }
+
+    #[test]
+    fn max_gap_stops_once_the_gap_is_small_enough() {
+        // The root lower bound is 5 and the greedy hitting set has 7 nodes
+        let (_, plain_report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        assert_eq!(plain_report.root_bounds.lower_bound(), 5);
+        assert_eq!(plain_report.root_bounds.greedy_upper, 7);
+        assert!(plain_report.branching_steps > 0);
+
+        let gap_settings = |max_gap| Settings {
+            max_gap: Some(max_gap),
+            ..settings()
+        };
+        let (hs, report) = solve(load(PETERSEN), String::new(), gap_settings(2)).unwrap();
+        assert!(hs.len() <= plain_report.opt + 2);
+        assert_eq!(report.branching_steps, 0);
+        assert!(!report.proven_optimal);
+
+        let (hs, report) = solve(load(PETERSEN), String::new(), gap_settings(0)).unwrap();
+        assert_eq!(hs.len(), plain_report.opt);
+        assert!(report.proven_optimal);
+    }
+
+    #[test]
+    fn initial_gap_of_the_petersen_graph() {
+        let (_, report) = solve(load(PETERSEN), String::new(), settings()).unwrap();
+        let bounds = &report.root_bounds;
+        assert_eq!(
+            bounds.initial_gap(),
+            bounds.greedy_upper - bounds.lower_bound()
+        );
+        // Greedy takes 7 nodes, the packing bound is 5
+        assert_eq!(bounds.initial_gap(), 2);
+        assert_eq!(bounds.difficulty(), "moderate");
+
+        let path = load("4 3\n2 0 1\n2 1 2\n2 2 3\n");
+        let (_, report) = solve(path, String::new(), settings()).unwrap();
+        assert_eq!(report.root_bounds.initial_gap(), 0);
+        assert_eq!(report.root_bounds.difficulty(), "trivial");
+    }
+
+    #[test]
+    fn compacted_kernel_reaches_the_plain_optimum() {
+        // The unit edge and the pendant path are reduced away, leaving the
+        // Petersen graph as kernel. The Petersen graph alone is irreducible.
+        let reducible = PETERSEN.replacen("10 15", "13 18", 1) + "1 10\n2 10 11\n2 11 12\n";
+        for text in [reducible.as_str(), PETERSEN] {
+            let (plain_hs, plain_report) = solve(load(text), String::new(), settings()).unwrap();
+            let compact_settings = Settings {
+                compact_kernel: true,
+                ..settings()
+            };
+            let (hs, report) = solve(load(text), String::new(), compact_settings).unwrap();
+            assert!(is_hitting_set(&hs, &load(text)));
+            assert_eq!(hs.len(), plain_hs.len());
+            assert_eq!(report.opt, plain_report.opt);
+            assert!(report.proven_optimal);
+            assert!(report.settings.compact_kernel);
+        }
+    }
+
+    #[test]
+    fn single_edge_instances_need_one_node() {
+        for text in ["1 1\n1 0\n", "2 1\n2 0 1\n", "5 1\n5 0 1 2 3 4\n"] {
+            let (hs, report) = solve(load(text), String::new(), settings()).unwrap();
+            assert_eq!(hs.len(), 1, "{text}");
+            assert!(is_hitting_set(&hs, &load(text)));
+            assert_eq!(report.opt, 1);
+            assert!(report.proven_optimal);
+            assert_eq!(report.branching_steps, 0);
+        }
+    }
+
+    #[test]
+    fn golden_results_of_small_instances() {
+        // Recorded with the default settings. A changed branching count is a
+        // behavioral change of the solver and should be reviewed.
+        let triangles = "10 17\n2 0 1\n2 0 3\n2 0 5\n2 0 6\n2 1 2\n2 1 6\n2 1 8\n2 1 9\n\
+                         2 2 4\n2 4 8\n2 5 6\n2 7 9\n4 0 2 5 7\n4 3 6 7 8\n5 0 1 3 5 6\n\
+                         5 2 4 6 7 9\n5 2 5 7 8 9\n";
+        let mixed = "16 40\n3 0 4 14\n3 5 12 15\n3 2 8 12\n2 4 9\n3 5 6 14\n3 2 10 15\n\
+                     3 2 13 14\n2 0 2\n2 8 12\n2 9 11\n3 2 7 13\n2 1 9\n2 0 11\n2 6 11\n\
+                     2 6 8\n3 1 11 13\n2 4 14\n2 5 9\n3 8 14 15\n2 9 10\n2 0 1\n3 1 3 8\n\
+                     2 1 5\n2 1 11\n2 1 8\n2 7 9\n3 3 8 12\n3 3 6 15\n2 7 15\n3 1 9 15\n\
+                     2 3 5\n2 3 8\n3 4 10 12\n3 6 14 15\n2 0 6\n3 1 2 15\n2 2 6\n3 2 4 9\n\
+                     3 2 4 6\n3 0 4 5\n";
+        let golden = [
+            ("4 3\n2 0 1\n2 1 2\n2 2 3\n", 2, 0),
+            ("5 5\n2 0 1\n2 1 2\n2 2 3\n2 3 4\n2 0 4\n", 3, 0),
+            (PETERSEN, 6, 2),
+            (triangles, 5, 1),
+            (mixed, 8, 2),
+        ];
+        for (text, opt, branching_steps) in golden {
+            let (hs, report) = solve(load(text), String::new(), settings()).unwrap();
+            assert!(is_hitting_set(&hs, &load(text)));
+            assert_eq!(hs.len(), opt);
+            assert_eq!(report.opt, opt);
+            assert!(report.proven_optimal);
+            assert_eq!(report.branching_steps, branching_steps);
+        }
+    }
+}